@@ -0,0 +1,51 @@
+use flatvec::prelude::*;
+
+fn main() {
+    let mut names: FlatVec<_, u32, u8, 3> = FlatVec::new();
+    // Insert an owned type, extract a borrowed type
+    names.push(DomainName {
+        ttl: 60,
+        time_seen: 31415,
+        name: b"google.com".to_vec(),
+    });
+    assert_eq!(
+        names.get::<DomainNameRef>(0).unwrap(),
+        DomainNameRef {
+            ttl: 60,
+            time_seen: 31415,
+            name: &b"google.com"[..],
+        }
+    );
+
+    names.clear();
+    // Insert a borrowed type, extract an owned type
+    // With the same FlatVec
+    names.push(DomainNameRef {
+        ttl: 60,
+        time_seen: 31415,
+        name: &b"google.com"[..],
+    });
+    assert_eq!(
+        names.get::<DomainName>(0).unwrap(),
+        DomainName {
+            ttl: 60,
+            time_seen: 31415,
+            name: b"google.com".to_vec(),
+        }
+    );
+}
+
+#[derive(Flatten, PartialEq, Eq, Debug)]
+pub struct DomainName {
+    ttl: u32,
+    time_seen: u32,
+    name: Vec<u8>,
+}
+
+#[derive(Flatten, PartialEq, Eq, Debug)]
+#[flatvec(flattened = DomainName)]
+pub struct DomainNameRef<'a> {
+    ttl: u32,
+    time_seen: u32,
+    name: &'a [u8],
+}
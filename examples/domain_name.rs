@@ -1,4 +1,4 @@
-use flatvec::{FlatVec, FromFlat, IntoFlat, Storage};
+use flatvec::prelude::*;
 
 fn main() {
     let mut names: FlatVec<_, u32, u8, 3> = FlatVec::new();
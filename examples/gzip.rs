@@ -1,4 +1,4 @@
-use flatvec::{FlatVec, FromFlat, IntoFlat, Storage};
+use flatvec::{FlatVec, FromFlat, IntoFlat, Storage, StorageWriter};
 
 fn main() {
     let mut vec: FlatVec<CompressedBytes, usize, _, 3> = FlatVec::default();
@@ -10,26 +10,13 @@ fn main() {
     assert_eq!(&out, &data_to_insert);
 }
 
-struct WriteAdapter<'a>(Storage<'a, u8>);
-
-impl std::io::Write for WriteAdapter<'_> {
-    fn write(&mut self, data: &[u8]) -> std::io::Result<usize> {
-        self.0.extend(data.iter().cloned());
-        Ok(data.len())
-    }
-
-    fn flush(&mut self) -> std::io::Result<()> {
-        Ok(())
-    }
-}
-
-struct CompressedBytes(Vec<u8>);
+struct CompressedBytes;
 
 impl IntoFlat<u8, CompressedBytes> for &[u8] {
     fn into_flat(self, store: Storage<u8>) {
         use std::io::Write;
-        let mut encoder = libflate::gzip::Encoder::new(WriteAdapter(store)).unwrap();
-        encoder.write_all(&self).unwrap();
+        let mut encoder = libflate::gzip::Encoder::new(StorageWriter(store)).unwrap();
+        encoder.write_all(self).unwrap();
         encoder.finish().unwrap();
     }
 }
@@ -0,0 +1,205 @@
+//! A derive macro for implementing [flatvec](https://crates.io/crates/flatvec)'s `IntoFlat` and
+//! `FromFlat` traits.
+//!
+//! `#[derive(Flatten)]` supports structs whose fields are all fixed-size primitives (integers and
+//! floats), optionally followed by one trailing `Vec<u8>` or `&[u8]` field. The fixed-size fields
+//! are stored first in declaration order with `to_ne_bytes`, followed by the contents of the
+//! trailing field. The derive generates `IntoFlat<u8, Flattened> for Self` and
+//! `FromFlat<'a, u8, Flattened> for Self`, so an owned type and a borrowed handle type can share
+//! a representation:
+//! ```rust
+//! use flatvec_derive::Flatten;
+//!
+//! #[derive(Flatten)]
+//! struct DomainName {
+//!     ttl: u32,
+//!     time_seen: u32,
+//!     name: Vec<u8>,
+//! }
+//!
+//! #[derive(Flatten)]
+//! #[flatvec(flattened = DomainName)]
+//! struct DomainNameRef<'a> {
+//!     ttl: u32,
+//!     time_seen: u32,
+//!     name: &'a [u8],
+//! }
+//!
+//! let mut names: flatvec::FlatVec<DomainName, usize, u8, 3> = flatvec::FlatVec::new();
+//! names.push(DomainNameRef { ttl: 60, time_seen: 31415, name: b"google.com" });
+//! assert_eq!(names.get::<DomainName>(0).unwrap().name, b"google.com");
+//! ```
+//! `Flattened` is `Self` unless it is set with `#[flatvec(flattened = Type)]`.
+
+use proc_macro::TokenStream;
+use proc_macro2::TokenStream as TokenStream2;
+use quote::quote;
+use syn::{
+    parse_macro_input, parse_quote, Data, DeriveInput, Error, GenericArgument, GenericParam,
+    Member, PathArguments, Type,
+};
+
+#[proc_macro_derive(Flatten, attributes(flatvec))]
+pub fn derive_flatten(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    expand(input)
+        .unwrap_or_else(Error::into_compile_error)
+        .into()
+}
+
+// How the last field of a struct is stored
+enum Tail {
+    Owned,
+    Borrowed,
+}
+
+fn expand(input: DeriveInput) -> syn::Result<TokenStream2> {
+    let name = &input.ident;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+
+    let mut flattened: Type = parse_quote!(#name #ty_generics);
+    for attr in &input.attrs {
+        if attr.path().is_ident("flatvec") {
+            attr.parse_nested_meta(|meta| {
+                if meta.path.is_ident("flattened") {
+                    flattened = meta.value()?.parse()?;
+                    Ok(())
+                } else {
+                    Err(meta.error("unsupported flatvec attribute"))
+                }
+            })?;
+        }
+    }
+
+    let mut lifetime = None;
+    for param in &input.generics.params {
+        match param {
+            GenericParam::Lifetime(param) if lifetime.is_none() => {
+                lifetime = Some(param.lifetime.clone());
+            }
+            _ => {
+                return Err(Error::new_spanned(
+                    param,
+                    "Flatten can only be derived for structs with at most one lifetime parameter",
+                ))
+            }
+        }
+    }
+    let lifetime = lifetime.unwrap_or_else(|| parse_quote!('flatvec));
+
+    let fields = match &input.data {
+        Data::Struct(data) => &data.fields,
+        _ => {
+            return Err(Error::new_spanned(
+                &input.ident,
+                "Flatten can only be derived for structs",
+            ))
+        }
+    };
+    let mut fields = fields
+        .iter()
+        .enumerate()
+        .map(|(i, field)| {
+            let member = match &field.ident {
+                Some(ident) => Member::Named(ident.clone()),
+                None => Member::Unnamed(i.into()),
+            };
+            (member, &field.ty)
+        })
+        .collect::<Vec<_>>();
+
+    let tail = match fields.last() {
+        Some((_, ty)) if is_byte_vec(ty) => Some(Tail::Owned),
+        Some((_, ty)) if is_byte_slice(ty) => Some(Tail::Borrowed),
+        _ => None,
+    };
+    let tail = tail.map(|tail| (fields.pop().unwrap().0, tail));
+
+    // The fixed-size fields are laid out back-to-back, in declaration order
+    let mut offset = quote!(0);
+    let mut writes = Vec::new();
+    let mut reads = Vec::new();
+    for (member, ty) in &fields {
+        let start = offset.clone();
+        offset = quote!(#offset + ::core::mem::size_of::<#ty>());
+        writes.push(quote! {
+            data[#start..#offset].copy_from_slice(&self.#member.to_ne_bytes());
+        });
+        reads.push(quote! {
+            #member: <#ty>::from_ne_bytes(
+                ::core::convert::TryInto::try_into(&data[#start..#offset]).unwrap()
+            ),
+        });
+    }
+
+    let (tail_len, tail_write, tail_read) = match &tail {
+        Some((member, tail)) => {
+            let read = match tail {
+                Tail::Owned => quote!(#member: data[#offset..].to_vec(),),
+                Tail::Borrowed => quote!(#member: &data[#offset..],),
+            };
+            (
+                quote!(self.#member.len()),
+                quote!(data[#offset..].copy_from_slice(&self.#member[..]);),
+                read,
+            )
+        }
+        None => (quote!(0), quote!(), quote!()),
+    };
+
+    Ok(quote! {
+        impl #impl_generics ::flatvec::IntoFlat<u8, #flattened> for #name #ty_generics
+        #where_clause
+        {
+            fn into_flat(self, mut store: ::flatvec::Storage<u8>) {
+                let data = store.allocate(#offset + #tail_len);
+                #(#writes)*
+                #tail_write
+            }
+        }
+
+        impl<#lifetime> ::flatvec::FromFlat<#lifetime, u8, #flattened> for #name #ty_generics
+        #where_clause
+        {
+            fn from_flat(data: &#lifetime [u8]) -> Self {
+                assert!(data.len() >= #offset);
+                Self {
+                    #(#reads)*
+                    #tail_read
+                }
+            }
+        }
+    })
+}
+
+fn is_u8(ty: &Type) -> bool {
+    matches!(ty, Type::Path(path) if path.qself.is_none() && path.path.is_ident("u8"))
+}
+
+// Vec<u8>
+fn is_byte_vec(ty: &Type) -> bool {
+    let path = match ty {
+        Type::Path(path) if path.qself.is_none() => &path.path,
+        _ => return false,
+    };
+    let segment = match path.segments.last() {
+        Some(segment) if segment.ident == "Vec" => segment,
+        _ => return false,
+    };
+    match &segment.arguments {
+        PathArguments::AngleBracketed(args) if args.args.len() == 1 => {
+            matches!(&args.args[0], GenericArgument::Type(ty) if is_u8(ty))
+        }
+        _ => false,
+    }
+}
+
+// &[u8]
+fn is_byte_slice(ty: &Type) -> bool {
+    match ty {
+        Type::Reference(reference) if reference.mutability.is_none() => {
+            matches!(&*reference.elem, Type::Slice(slice) if is_u8(&slice.elem))
+        }
+        _ => false,
+    }
+}
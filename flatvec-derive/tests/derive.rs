@@ -0,0 +1,61 @@
+use flatvec::FlatVec;
+use flatvec_derive::Flatten;
+
+#[derive(Flatten, PartialEq, Eq, Debug)]
+struct DomainName {
+    ttl: u32,
+    time_seen: u64,
+    name: Vec<u8>,
+}
+
+#[derive(Flatten, PartialEq, Eq, Debug)]
+#[flatvec(flattened = DomainName)]
+struct DomainNameRef<'a> {
+    ttl: u32,
+    time_seen: u64,
+    name: &'a [u8],
+}
+
+#[derive(Flatten, PartialEq, Debug)]
+struct Point(f32, i16);
+
+#[test]
+fn owned_and_borrowed() {
+    let mut names: FlatVec<DomainName, usize, u8, 3> = FlatVec::new();
+    names.push(DomainName {
+        ttl: 60,
+        time_seen: 31415,
+        name: b"google.com".to_vec(),
+    });
+    names.push(DomainNameRef {
+        ttl: 30,
+        time_seen: 27182,
+        name: b"rust-lang.org",
+    });
+    assert_eq!(names.element_len(0), Some(4 + 8 + 10));
+    assert_eq!(
+        names.get::<DomainNameRef>(0),
+        Some(DomainNameRef {
+            ttl: 60,
+            time_seen: 31415,
+            name: b"google.com",
+        })
+    );
+    assert_eq!(
+        names.get::<DomainName>(1),
+        Some(DomainName {
+            ttl: 30,
+            time_seen: 27182,
+            name: b"rust-lang.org".to_vec(),
+        })
+    );
+}
+
+#[test]
+fn fixed_size_only() {
+    let mut points: FlatVec<Point, usize, u8, 3> = FlatVec::new();
+    points.push(Point(1.5, -3));
+    points.push(Point(-0.25, 7));
+    assert_eq!(points.element_len(0), Some(6));
+    assert_eq!(points.get(1), Some(Point(-0.25, 7)));
+}
@@ -0,0 +1,51 @@
+use crate::FlatVec;
+use arbitrary::{Arbitrary, Result, Unstructured};
+use core::{convert::TryFrom, fmt, ops::Sub};
+
+/// Generates a `FlatVec` of arbitrary byte strings. The elements are not necessarily valid for
+/// any particular `FromFlat` implementation, so for example they may not be valid UTF-8.
+///
+/// Elements are only added if their end offset can be represented by `IndexTy`, so generating a
+/// `FlatVec` never panics.
+impl<'a, T, IndexTy, const INDEX_INLINE_LEN: usize> Arbitrary<'a>
+    for FlatVec<T, IndexTy, u8, INDEX_INLINE_LEN>
+where
+    IndexTy: Default,
+    IndexTy: TryFrom<usize> + Copy + Sub,
+    usize: TryFrom<IndexTy>,
+    <IndexTy as TryFrom<usize>>::Error: fmt::Debug,
+    <usize as TryFrom<IndexTy>>::Error: fmt::Debug,
+{
+    #[inline]
+    fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+        let mut vec = Self::new();
+        for element in u.arbitrary_iter::<&[u8]>()? {
+            let element = element?;
+            // Elements which would overflow IndexTy are skipped
+            let _ = vec.try_push_with(|mut storage| storage.extend_from_slice(element));
+        }
+        Ok(vec)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::FlatVec;
+    use alloc::{string::String, vec::Vec};
+    use arbitrary::{Arbitrary, Unstructured};
+
+    #[test]
+    fn arbitrary() {
+        let bytes = (0..=255).rev().cycle().take(4096).collect::<Vec<u8>>();
+        let vec = FlatVec::<String, u16, u8, 3>::arbitrary(&mut Unstructured::new(&bytes)).unwrap();
+        assert!(!vec.is_empty());
+        assert!(vec.data_len() <= bytes.len());
+
+        // Elements which don't fit in a u8 are skipped
+        let vec = FlatVec::<String, u8, u8, 3>::arbitrary(&mut Unstructured::new(&bytes)).unwrap();
+        assert!(vec.data_len() <= u8::MAX as usize);
+
+        let empty = FlatVec::<String, u8, u8, 3>::arbitrary(&mut Unstructured::new(&[])).unwrap();
+        assert!(empty.is_empty());
+    }
+}
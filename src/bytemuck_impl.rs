@@ -0,0 +1,114 @@
+use crate::{FlatVec, Storage};
+use bytemuck::Pod;
+use core::{
+    convert::TryFrom,
+    fmt,
+    mem::{size_of, size_of_val},
+    ops::Sub,
+};
+
+impl<BackingTy> Storage<'_, BackingTy>
+where
+    BackingTy: Pod + Default,
+{
+    /// Inserts the bytes of `slice`, which are reinterpreted as `BackingTy`. If `slice` has no
+    /// bytes, such as when `U` is zero-sized, nothing is inserted.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the size of `slice` in bytes is not a multiple of the size of `BackingTy`, which
+    /// is always the case for a zero-sized `BackingTy` if `slice` has any bytes.
+    #[inline]
+    pub fn extend_from_pod_slice<U: Pod>(&mut self, slice: &[U]) {
+        if size_of_val(slice) == 0 {
+            return;
+        }
+        let bytes: &[u8] = bytemuck::cast_slice(slice);
+        let size = size_of::<BackingTy>();
+        assert!(
+            bytes.len().is_multiple_of(size),
+            "a slice of {} bytes cannot be stored as a whole number of BackingTy",
+            bytes.len()
+        );
+        let data: &mut [u8] = bytemuck::cast_slice_mut(self.allocate(bytes.len() / size));
+        data.copy_from_slice(bytes);
+    }
+}
+
+impl<T, IndexTy, BackingTy, const INDEX_INLINE_LEN: usize>
+    FlatVec<T, IndexTy, BackingTy, INDEX_INLINE_LEN>
+where
+    IndexTy: Default,
+    IndexTy: TryFrom<usize> + Copy + Sub,
+    usize: TryFrom<IndexTy>,
+    <IndexTy as TryFrom<usize>>::Error: fmt::Debug,
+    <usize as TryFrom<IndexTy>>::Error: fmt::Debug,
+    BackingTy: Pod,
+{
+    /// Returns the stored representation of the `index`th element reinterpreted as a slice of
+    /// `U`, or `None` if `index` is out of bounds or the element cannot be reinterpreted.
+    ///
+    /// The backing storage of each element is only guaranteed to be aligned to
+    /// `align_of::<BackingTy>()`, so this may return `None` for a `U` with a larger alignment,
+    /// depending on where the element happens to be stored.
+    #[inline]
+    #[must_use]
+    pub fn get_pod_slice<U: Pod>(&self, index: usize) -> Option<&[U]> {
+        bytemuck::try_cast_slice(self.raw_get(index)?).ok()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{FlatVec, IntoFlat, Storage};
+    use alloc::vec::Vec;
+
+    struct Points<'a>(&'a [[u16; 2]]);
+
+    impl<BackingTy: bytemuck::Pod + Default> IntoFlat<BackingTy, Vec<BackingTy>> for Points<'_> {
+        fn into_flat(self, mut storage: Storage<'_, BackingTy>) {
+            storage.extend_from_pod_slice(self.0);
+        }
+    }
+
+    #[test]
+    fn pod_slice() {
+        let mut points: FlatVec<Vec<u8>, usize, u8, 3> = FlatVec::new();
+        points.push(Points(&[[1, 2], [3, 4]]));
+        assert_eq!(points.data_len(), 8);
+        assert_eq!(points.get_pod_slice::<u8>(0).unwrap().len(), 8);
+        assert_eq!(points.get_pod_slice::<u8>(1), None);
+
+        let mut wide: FlatVec<Vec<u16>, usize, u16, 3> = FlatVec::new();
+        wide.push(0..3);
+        assert_eq!(wide.get_pod_slice::<[u16; 3]>(0), Some(&[[0, 1, 2]][..]));
+        assert_eq!(wide.get_pod_slice::<[u16; 2]>(0), None);
+    }
+
+    struct Units<'a>(&'a [()]);
+
+    impl<BackingTy: bytemuck::Pod + Default> IntoFlat<BackingTy, Vec<BackingTy>> for Units<'_> {
+        fn into_flat(self, mut storage: Storage<'_, BackingTy>) {
+            storage.extend_from_pod_slice(self.0);
+        }
+    }
+
+    #[test]
+    fn pod_slice_zero_sized() {
+        let mut points: FlatVec<Vec<u8>, usize, u8, 3> = FlatVec::new();
+        points.push(Units(&[(), ()]));
+        assert_eq!((points.len(), points.data_len()), (1, 0));
+
+        let mut units: FlatVec<Vec<()>, usize, (), 3> = FlatVec::new();
+        units.push(Units(&[()]));
+        units.push(Points(&[]));
+        assert_eq!((units.len(), units.data_len()), (2, 0));
+    }
+
+    #[test]
+    #[should_panic]
+    fn pod_slice_zero_sized_backing() {
+        let mut units: FlatVec<Vec<()>, usize, (), 3> = FlatVec::new();
+        units.push(Points(&[[1, 2]]));
+    }
+}
@@ -0,0 +1,452 @@
+use crate::{FlatVec, FromFlat, DEFAULT_GROWTH_FACTOR};
+use alloc::{boxed::Box, vec::Vec};
+use core::{
+    cmp,
+    convert::{TryFrom, TryInto},
+    fmt,
+    marker::PhantomData,
+    mem,
+    ops::Sub,
+};
+use tinyvec::TinyVec;
+
+/// A read-only `FlatVec` which stores its end offsets in the same heap allocation as its backing
+/// storage, created by `FlatVec::into_compact`.
+///
+/// The allocation holds the backing storage of every element, followed by the end offset of each
+/// element split into as many `BackingTy` as it takes to hold an integer as wide as `IndexTy`. So
+/// however many elements it has, a `CompactFlatVec` holds at most one heap allocation, which is
+/// exactly as large as it needs to be.
+///
+/// `BackingTy` must be an integer type, so that it can hold the pieces of an end offset.
+#[derive(Clone)]
+pub struct CompactFlatVec<T, IndexTy, BackingTy> {
+    data: Box<[BackingTy]>,
+    data_len: usize,
+    len: usize,
+    encoding: EndEncoding,
+    marker: PhantomData<(T, IndexTy)>,
+}
+
+// How each end offset is split into BackingTy
+#[derive(Clone, Copy, Debug)]
+struct EndEncoding {
+    // The number of bits of the end offset stored in each BackingTy
+    bits: u32,
+    // The number of BackingTy used to store each end offset
+    per_end: usize,
+}
+
+impl EndEncoding {
+    #[inline]
+    fn new<IndexTy, BackingTy>() -> Self
+    where
+        BackingTy: TryFrom<u64>,
+    {
+        assert!(
+            mem::size_of::<BackingTy>() > 0,
+            "a zero-sized BackingTy cannot store end offsets"
+        );
+        let bits = cmp::min(8 * mem::size_of::<BackingTy>(), 64) as u32;
+        // A signed BackingTy cannot hold a value with its top bit set
+        let bits = if BackingTy::try_from(u64::MAX >> (64 - bits)).is_ok() {
+            bits
+        } else {
+            bits - 1
+        };
+        // An end offset never exceeds data_len, so the bits beyond the width of a usize are 0
+        let offset_bits = 8 * cmp::min(mem::size_of::<IndexTy>(), mem::size_of::<usize>()) as u32;
+        let per_end = cmp::max(1, offset_bits.div_ceil(bits)) as usize;
+        Self { bits, per_end }
+    }
+
+    #[inline]
+    fn encode<BackingTy>(self, end: usize, out: &mut Vec<BackingTy>)
+    where
+        BackingTy: TryFrom<u64>,
+        <BackingTy as TryFrom<u64>>::Error: fmt::Debug,
+    {
+        let end = u64::try_from(end).unwrap();
+        let mask = u64::MAX >> (64 - self.bits);
+        for piece in 0..self.per_end as u32 {
+            let value = (end >> (piece * self.bits)) & mask;
+            out.push(BackingTy::try_from(value).unwrap());
+        }
+    }
+
+    // Decodes the index'th end offset stored in ends
+    #[inline]
+    fn decode<BackingTy>(self, ends: &[BackingTy], index: usize) -> usize
+    where
+        BackingTy: Copy,
+        u64: TryFrom<BackingTy>,
+        <u64 as TryFrom<BackingTy>>::Error: fmt::Debug,
+    {
+        let pieces = &ends[index * self.per_end..(index + 1) * self.per_end];
+        let end = pieces
+            .iter()
+            .zip((0..).step_by(self.bits as usize))
+            .fold(0, |end, (&piece, shift)| {
+                end | u64::try_from(piece).unwrap() << shift
+            });
+        end.try_into().unwrap()
+    }
+}
+
+impl<T, IndexTy, BackingTy> fmt::Debug for CompactFlatVec<T, IndexTy, BackingTy>
+where
+    BackingTy: fmt::Debug + Copy,
+    u64: TryFrom<BackingTy>,
+    <u64 as TryFrom<BackingTy>>::Error: fmt::Debug,
+{
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        let ends = &self.data[self.data_len..];
+        fmt.debug_struct("CompactFlatVec")
+            .field("data", &&self.data[..self.data_len])
+            .field(
+                "ends",
+                &(0..self.len)
+                    .map(|index| self.encoding.decode(ends, index))
+                    .collect::<Vec<_>>(),
+            )
+            .finish()
+    }
+}
+
+impl<'a, T: 'a, IndexTy, BackingTy> CompactFlatVec<T, IndexTy, BackingTy>
+where
+    BackingTy: Copy,
+    u64: TryFrom<BackingTy>,
+    <u64 as TryFrom<BackingTy>>::Error: fmt::Debug,
+{
+    /// Returns the number of elements in the `CompactFlatVec`.
+    #[inline]
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Returns true if the len is 0.
+    #[inline]
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Returns the number of `BackingTy` used to store the elements.
+    #[inline]
+    #[must_use]
+    pub fn data_len(&self) -> usize {
+        self.data_len
+    }
+
+    /// Returns the number of heap allocations held by the `CompactFlatVec`, which is at most 1.
+    #[inline]
+    #[must_use]
+    pub fn heap_allocations(&self) -> usize {
+        usize::from(self.data.len() * mem::size_of::<BackingTy>() > 0)
+    }
+
+    /// Construct a `Dest` from the `index`th element's stored representation.
+    #[inline]
+    #[must_use]
+    pub fn get<Dest>(&'a self, index: usize) -> Option<Dest>
+    where
+        Dest: FromFlat<'a, BackingTy, T> + 'a,
+    {
+        self.raw_get(index).map(Dest::from_flat)
+    }
+
+    /// Returns the stored representation of the `index`th element.
+    #[inline]
+    #[must_use]
+    pub fn raw_get(&self, index: usize) -> Option<&[BackingTy]> {
+        if index >= self.len {
+            None
+        } else {
+            let ends = &self.data[self.data_len..];
+            let start = match index.checked_sub(1) {
+                Some(previous) => self.encoding.decode(ends, previous),
+                None => 0,
+            };
+            Some(&self.data[start..self.encoding.decode(ends, index)])
+        }
+    }
+
+    /// Returns an iterator that constructs a `Dest` from each element's stored representation.
+    #[inline]
+    pub fn iter<Dest>(&'a self) -> CompactIter<'a, T, Dest, BackingTy>
+    where
+        Dest: FromFlat<'a, BackingTy, T> + 'a,
+    {
+        CompactIter {
+            raw: self.raw_iter(),
+            marker: PhantomData,
+        }
+    }
+
+    /// Returns an iterator over the stored representation of each element.
+    #[inline]
+    pub fn raw_iter(&self) -> CompactRawIter<'_, BackingTy> {
+        let (data, ends) = self.data.split_at(self.data_len);
+        CompactRawIter {
+            data,
+            ends,
+            start: 0,
+            encoding: self.encoding,
+        }
+    }
+}
+
+impl<T, IndexTy, BackingTy> CompactFlatVec<T, IndexTy, BackingTy>
+where
+    IndexTy: Default,
+    IndexTy: TryFrom<usize> + Copy + Sub,
+    usize: TryFrom<IndexTy>,
+    <IndexTy as TryFrom<usize>>::Error: fmt::Debug,
+    <usize as TryFrom<IndexTy>>::Error: fmt::Debug,
+    BackingTy: Copy,
+    u64: TryFrom<BackingTy>,
+    <u64 as TryFrom<BackingTy>>::Error: fmt::Debug,
+{
+    /// Converts the `CompactFlatVec` back into a `FlatVec`, which can be modified again. The
+    /// returned `FlatVec` has the default growth factor.
+    #[inline]
+    #[must_use]
+    pub fn into_flat_vec<const INDEX_INLINE_LEN: usize>(
+        self,
+    ) -> FlatVec<T, IndexTy, BackingTy, INDEX_INLINE_LEN> {
+        let () = FlatVec::<T, IndexTy, BackingTy, INDEX_INLINE_LEN>::INLINE_LEN_IS_NONZERO;
+        let encoded = &self.data[self.data_len..];
+        let ends: TinyVec<[IndexTy; INDEX_INLINE_LEN]> = (0..self.len)
+            .map(|index| self.encoding.decode(encoded, index).try_into().unwrap())
+            .collect();
+        let mut data = Vec::from(self.data);
+        data.truncate(self.data_len);
+        FlatVec {
+            data: data.into_boxed_slice(),
+            data_len: self.data_len,
+            ends,
+            growth_factor: DEFAULT_GROWTH_FACTOR,
+            marker: PhantomData,
+        }
+    }
+}
+
+impl<T, IndexTy, BackingTy, const INDEX_INLINE_LEN: usize>
+    FlatVec<T, IndexTy, BackingTy, INDEX_INLINE_LEN>
+where
+    IndexTy: Default,
+    IndexTy: TryFrom<usize> + Copy + Sub,
+    usize: TryFrom<IndexTy>,
+    <IndexTy as TryFrom<usize>>::Error: fmt::Debug,
+    <usize as TryFrom<IndexTy>>::Error: fmt::Debug,
+    BackingTy: TryFrom<u64>,
+    <BackingTy as TryFrom<u64>>::Error: fmt::Debug,
+{
+    /// Converts the `FlatVec` into a `CompactFlatVec`, which stores the end offsets after the
+    /// backing storage in a single heap allocation. This is the most compact representation of a
+    /// `FlatVec` which is done being built and is only read from afterwards.
+    ///
+    /// `BackingTy` must be an integer type, so that it can hold the pieces of each end offset.
+    ///
+    /// This function is `O(self.data_len() + self.len())`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `BackingTy` is zero-sized.
+    #[inline]
+    #[must_use]
+    pub fn into_compact(self) -> CompactFlatVec<T, IndexTy, BackingTy> {
+        let encoding = EndEncoding::new::<IndexTy, BackingTy>();
+        let (data, data_len, ends) = self.into_raw_parts();
+        let mut data = Vec::from(data);
+        data.truncate(data_len);
+        data.reserve_exact(ends.len() * encoding.per_end);
+        for &end in ends.iter() {
+            encoding.encode(end.try_into().unwrap(), &mut data);
+        }
+        CompactFlatVec {
+            data: data.into_boxed_slice(),
+            data_len,
+            len: ends.len(),
+            encoding,
+            marker: PhantomData,
+        }
+    }
+}
+
+/// An iterator that constructs a `Dest` from each element of a `CompactFlatVec`, created by
+/// `CompactFlatVec::iter`.
+pub struct CompactIter<'a, T, Dest, BackingTy> {
+    raw: CompactRawIter<'a, BackingTy>,
+    marker: PhantomData<fn() -> (T, Dest)>,
+}
+
+impl<'a, T, Dest, BackingTy> Iterator for CompactIter<'a, T, Dest, BackingTy>
+where
+    BackingTy: Copy,
+    u64: TryFrom<BackingTy>,
+    <u64 as TryFrom<BackingTy>>::Error: fmt::Debug,
+    Dest: FromFlat<'a, BackingTy, T>,
+{
+    type Item = Dest;
+
+    #[inline]
+    fn next(&mut self) -> Option<Dest> {
+        self.raw.next().map(Dest::from_flat)
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.raw.size_hint()
+    }
+}
+
+impl<'a, T, Dest, BackingTy> DoubleEndedIterator for CompactIter<'a, T, Dest, BackingTy>
+where
+    BackingTy: Copy,
+    u64: TryFrom<BackingTy>,
+    <u64 as TryFrom<BackingTy>>::Error: fmt::Debug,
+    Dest: FromFlat<'a, BackingTy, T>,
+{
+    #[inline]
+    fn next_back(&mut self) -> Option<Dest> {
+        self.raw.next_back().map(Dest::from_flat)
+    }
+}
+
+impl<'a, T, Dest, BackingTy> ExactSizeIterator for CompactIter<'a, T, Dest, BackingTy>
+where
+    BackingTy: Copy,
+    u64: TryFrom<BackingTy>,
+    <u64 as TryFrom<BackingTy>>::Error: fmt::Debug,
+    Dest: FromFlat<'a, BackingTy, T>,
+{
+}
+
+/// An iterator over the stored representation of each element of a `CompactFlatVec`, created by
+/// `CompactFlatVec::raw_iter`.
+#[derive(Clone)]
+pub struct CompactRawIter<'a, BackingTy> {
+    data: &'a [BackingTy],
+    // The encoded end offsets of the elements not yet yielded
+    ends: &'a [BackingTy],
+    // The start offset of the first element not yet yielded
+    start: usize,
+    encoding: EndEncoding,
+}
+
+impl<'a, BackingTy> Iterator for CompactRawIter<'a, BackingTy>
+where
+    BackingTy: Copy,
+    u64: TryFrom<BackingTy>,
+    <u64 as TryFrom<BackingTy>>::Error: fmt::Debug,
+{
+    type Item = &'a [BackingTy];
+
+    #[inline]
+    fn next(&mut self) -> Option<&'a [BackingTy]> {
+        if self.ends.is_empty() {
+            return None;
+        }
+        let end = self.encoding.decode(self.ends, 0);
+        let start = mem::replace(&mut self.start, end);
+        self.ends = &self.ends[self.encoding.per_end..];
+        Some(&self.data[start..end])
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let len = self.ends.len() / self.encoding.per_end;
+        (len, Some(len))
+    }
+}
+
+impl<'a, BackingTy> DoubleEndedIterator for CompactRawIter<'a, BackingTy>
+where
+    BackingTy: Copy,
+    u64: TryFrom<BackingTy>,
+    <u64 as TryFrom<BackingTy>>::Error: fmt::Debug,
+{
+    #[inline]
+    fn next_back(&mut self) -> Option<&'a [BackingTy]> {
+        let last = (self.ends.len() / self.encoding.per_end).checked_sub(1)?;
+        let end = self.encoding.decode(self.ends, last);
+        let start = match last.checked_sub(1) {
+            Some(previous) => self.encoding.decode(self.ends, previous),
+            None => self.start,
+        };
+        self.ends = &self.ends[..last * self.encoding.per_end];
+        Some(&self.data[start..end])
+    }
+}
+
+impl<BackingTy> ExactSizeIterator for CompactRawIter<'_, BackingTy>
+where
+    BackingTy: Copy,
+    u64: TryFrom<BackingTy>,
+    <u64 as TryFrom<BackingTy>>::Error: fmt::Debug,
+{
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::FlatVec;
+    use alloc::{string::String, vec::Vec};
+
+    #[test]
+    fn compact() {
+        let mut places: FlatVec<String, u16, u8, 1> = FlatVec::new();
+        places.extend(["Cyador", "", "Recluce", "Hamor"].iter().copied());
+        assert_eq!(places.heap_allocations(), 2);
+
+        let compact = places.into_compact();
+        assert_eq!(compact.heap_allocations(), 1);
+        assert_eq!((compact.len(), compact.data_len()), (4, 18));
+        assert_eq!(compact.get(0), Some("Cyador"));
+        assert_eq!(compact.get(1), Some(""));
+        assert_eq!(compact.raw_get(3), Some(&b"Hamor"[..]));
+        assert_eq!(compact.get::<&str>(4), None);
+        assert_eq!(
+            compact.iter().collect::<Vec<&str>>(),
+            ["Cyador", "", "Recluce", "Hamor"]
+        );
+        assert_eq!(
+            compact.iter().rev().collect::<Vec<&str>>(),
+            ["Hamor", "Recluce", "", "Cyador"]
+        );
+        assert_eq!(compact.raw_iter().len(), 4);
+
+        let mut places = compact.into_flat_vec::<3>();
+        assert_eq!(places.ends(), [6, 6, 13, 18]);
+        places.push("Sarronnyn");
+        assert_eq!(places.get(4), Some("Sarronnyn"));
+
+        let empty = FlatVec::<String, usize, u8, 3>::new().into_compact();
+        assert!(empty.is_empty());
+        assert_eq!(empty.heap_allocations(), 0);
+    }
+
+    #[test]
+    fn compact_wide_backing() {
+        let mut wide: FlatVec<Vec<u16>, u32, u16, 1> = FlatVec::new();
+        wide.push(0..3);
+        wide.push(3..3);
+        wide.push(3..1000);
+        let wide = wide.into_compact();
+        assert_eq!(wide.heap_allocations(), 1);
+        assert_eq!(wide.get(0), Some(&[0, 1, 2][..]));
+        assert_eq!(wide.raw_get(1), Some(&[][..]));
+        assert_eq!(wide.raw_iter().map(<[u16]>::len).sum::<usize>(), 1000);
+        assert_eq!(wide.into_flat_vec::<1>().ends(), [3, 3, 1000]);
+
+        let mut signed: FlatVec<Vec<i8>, usize, i8, 1> = FlatVec::new();
+        signed.extend((0..200).map(|_| [-1i8; 1].iter().copied()));
+        let signed = signed.into_compact();
+        assert_eq!(signed.len(), 200);
+        assert_eq!(signed.get(199), Some(&[-1][..]));
+        assert_eq!(signed.raw_iter().rev().nth(150), Some(&[-1][..]));
+        assert_eq!(signed.into_flat_vec::<1>().ends()[199], 200);
+    }
+}
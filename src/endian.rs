@@ -0,0 +1,211 @@
+use crate::{FromFlat, IntoFlat, Storage};
+use alloc::boxed::Box;
+use core::{convert::TryInto, mem::size_of};
+
+macro_rules! storage_writes {
+    ($($ty:ident => $le:ident, $be:ident, $ne:ident;)*) => {
+        impl Storage<'_, u8> {
+            $(
+                #[doc = concat!("Inserts the little-endian bytes of a `", stringify!($ty), "`.")]
+                #[inline]
+                pub fn $le(&mut self, value: $ty) {
+                    self.extend_from_slice(&value.to_le_bytes());
+                }
+
+                #[doc = concat!("Inserts the big-endian bytes of a `", stringify!($ty), "`.")]
+                #[inline]
+                pub fn $be(&mut self, value: $ty) {
+                    self.extend_from_slice(&value.to_be_bytes());
+                }
+
+                #[doc = concat!("Inserts the native-endian bytes of a `", stringify!($ty), "`.")]
+                #[inline]
+                pub fn $ne(&mut self, value: $ty) {
+                    self.extend_from_slice(&value.to_ne_bytes());
+                }
+            )*
+        }
+    };
+}
+
+storage_writes! {
+    u16 => write_u16_le, write_u16_be, write_u16_ne;
+    u32 => write_u32_le, write_u32_be, write_u32_ne;
+    u64 => write_u64_le, write_u64_be, write_u64_ne;
+    i16 => write_i16_le, write_i16_be, write_i16_ne;
+    i32 => write_i32_le, write_i32_be, write_i32_ne;
+    i64 => write_i64_le, write_i64_be, write_i64_ne;
+}
+
+macro_rules! numeric_slices {
+    ($($ty:ident),*) => {
+        $(
+            impl IntoFlat<$ty, Box<[$ty]>> for &[$ty] {
+                #[inline]
+                fn into_flat(self, mut store: Storage<$ty>) {
+                    store.extend_from_slice(self);
+                }
+            }
+
+            impl<'a> FromFlat<'a, $ty, Box<[$ty]>> for &'a [$ty] {
+                #[inline]
+                fn from_flat(data: &'a [$ty]) -> &'a [$ty] {
+                    data
+                }
+            }
+
+            impl FromFlat<'_, $ty, Box<[$ty]>> for Box<[$ty]> {
+                #[inline]
+                fn from_flat(data: &[$ty]) -> Self {
+                    data.into()
+                }
+            }
+        )*
+    };
+}
+
+numeric_slices!(u16, u32, u64, i16, i32, i64);
+
+/// Reads fields from the front of an element's stored representation, in the order they were
+/// inserted into its `Storage`, for use in `FromFlat` implementations.
+///
+/// # Panics
+///
+/// Every read panics if there are not enough bytes left for it.
+#[derive(Clone, Debug)]
+pub struct FlatReader<'a> {
+    data: &'a [u8],
+}
+
+macro_rules! reader_reads {
+    ($($ty:ident => $le:ident, $be:ident, $ne:ident;)*) => {
+        impl FlatReader<'_> {
+            $(
+                #[doc = concat!("Reads a little-endian `", stringify!($ty), "`.")]
+                #[inline]
+                pub fn $le(&mut self) -> $ty {
+                    $ty::from_le_bytes(self.take(size_of::<$ty>()).try_into().unwrap())
+                }
+
+                #[doc = concat!("Reads a big-endian `", stringify!($ty), "`.")]
+                #[inline]
+                pub fn $be(&mut self) -> $ty {
+                    $ty::from_be_bytes(self.take(size_of::<$ty>()).try_into().unwrap())
+                }
+
+                #[doc = concat!("Reads a native-endian `", stringify!($ty), "`.")]
+                #[inline]
+                pub fn $ne(&mut self) -> $ty {
+                    $ty::from_ne_bytes(self.take(size_of::<$ty>()).try_into().unwrap())
+                }
+            )*
+        }
+    };
+}
+
+reader_reads! {
+    u16 => read_u16_le, read_u16_be, read_u16_ne;
+    u32 => read_u32_le, read_u32_be, read_u32_ne;
+    u64 => read_u64_le, read_u64_be, read_u64_ne;
+    i16 => read_i16_le, read_i16_be, read_i16_ne;
+    i32 => read_i32_le, read_i32_be, read_i32_ne;
+    i64 => read_i64_le, read_i64_be, read_i64_ne;
+}
+
+impl<'a> FlatReader<'a> {
+    /// Create a `FlatReader` whose read cursor starts at the beginning of `data`. Each read
+    /// advances the cursor, and a read which would go past the end of `data` panics.
+    #[inline]
+    #[must_use]
+    pub fn new(data: &'a [u8]) -> Self {
+        Self { data }
+    }
+
+    /// Reads the next `len` bytes.
+    #[inline]
+    pub fn take(&mut self, len: usize) -> &'a [u8] {
+        assert!(
+            len <= self.data.len(),
+            "cannot read {} bytes, only {} are left",
+            len,
+            self.data.len()
+        );
+        let (taken, rest) = self.data.split_at(len);
+        self.data = rest;
+        taken
+    }
+
+    /// Returns the bytes which have not been read yet, such as a trailing variable-length field.
+    #[inline]
+    #[must_use]
+    pub fn rest(&self) -> &'a [u8] {
+        self.data
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::FlatReader;
+    use crate::{FlatVec, FromFlat, IntoFlat, Storage};
+    use alloc::{boxed::Box, vec, vec::Vec};
+
+    struct Record;
+
+    impl IntoFlat<u8, Record> for (u32, i16, &[u8]) {
+        fn into_flat(self, mut store: Storage<u8>) {
+            store.write_u32_be(self.0);
+            store.write_i16_le(self.1);
+            store.extend_from_slice(self.2);
+        }
+    }
+
+    impl<'a> FromFlat<'a, u8, Record> for (u32, i16, &'a [u8]) {
+        fn from_flat(data: &'a [u8]) -> Self {
+            let mut reader = FlatReader::new(data);
+            (reader.read_u32_be(), reader.read_i16_le(), reader.rest())
+        }
+    }
+
+    #[test]
+    fn endian() {
+        let mut records: FlatVec<Record, usize, u8, 3> = FlatVec::new();
+        records.push((0x0102_0304, -2, &b"Anya"[..]));
+        assert_eq!(
+            records.raw_get(0),
+            Some(&b"\x01\x02\x03\x04\xfe\xffAnya"[..])
+        );
+        assert_eq!(records.get(0), Some((0x0102_0304, -2, &b"Anya"[..])));
+
+        let mut numbers: FlatVec<Vec<u8>, usize, u8, 3> = FlatVec::new();
+        numbers.push_with(|mut store| {
+            store.write_u64_ne(7);
+            store.write_u16_be(8);
+        });
+        let mut reader = FlatReader::new(numbers.raw_get(0).unwrap());
+        assert_eq!(reader.read_u64_ne(), 7);
+        assert_eq!(reader.take(2), vec![0, 8]);
+        assert!(reader.rest().is_empty());
+    }
+
+    #[test]
+    fn numeric_slices() {
+        let mut samples: FlatVec<Box<[u32]>, usize, u32, 3> = FlatVec::new();
+        samples.push(&[1, 2, 3][..]);
+        samples.push(&[][..]);
+        samples.push(&[u32::MAX][..]);
+        assert_eq!(samples.data_len(), 4);
+        assert_eq!(samples.get(0), Some(&[1, 2, 3][..]));
+        assert_eq!(samples.get::<&[u32]>(1), Some(&[][..]));
+        assert_eq!(samples.get(2), Some(Box::from([u32::MAX])));
+
+        let mut deltas: FlatVec<Box<[i16]>, u8, i16, 3> = FlatVec::new();
+        deltas.push(&[-1, 0, 1][..]);
+        assert_eq!(deltas.to_vec::<Box<[i16]>>(), [Box::from([-1, 0, 1])]);
+    }
+
+    #[test]
+    #[should_panic]
+    fn read_past_end() {
+        FlatReader::new(&[1, 2, 3]).read_u32_le();
+    }
+}
@@ -0,0 +1,360 @@
+use crate::{resolve_range, FlatVec, FromFlat, Iter, Offsets, RawIter};
+use core::{
+    convert::TryFrom,
+    fmt,
+    marker::PhantomData,
+    ops::{Range, RangeBounds, Sub},
+};
+
+/// A borrowed view of a contiguous range of the elements of a `FlatVec`, which shares its
+/// backing storage and end offsets.
+pub struct FlatSlice<'a, T, IndexTy, BackingTy> {
+    data: &'a [BackingTy],
+    // The end offsets of the elements in the view
+    ends: &'a [IndexTy],
+    // The start offset of the first element in the view
+    start: usize,
+    marker: PhantomData<fn() -> T>,
+}
+
+impl<T, IndexTy, BackingTy> Clone for FlatSlice<'_, T, IndexTy, BackingTy> {
+    #[inline]
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<T, IndexTy, BackingTy> Copy for FlatSlice<'_, T, IndexTy, BackingTy> {}
+
+impl<T, IndexTy, BackingTy> fmt::Debug for FlatSlice<'_, T, IndexTy, BackingTy>
+where
+    IndexTy: fmt::Debug + Copy,
+    usize: TryFrom<IndexTy>,
+    <usize as TryFrom<IndexTy>>::Error: fmt::Debug,
+    BackingTy: fmt::Debug,
+{
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        fmt.debug_struct("FlatSlice")
+            .field("data", &&self.data[self.data_range()])
+            .field("ends", &self.ends)
+            .finish()
+    }
+}
+
+impl<'a, T: 'a, IndexTy, BackingTy> FlatSlice<'a, T, IndexTy, BackingTy>
+where
+    IndexTy: Copy,
+    usize: TryFrom<IndexTy>,
+    <usize as TryFrom<IndexTy>>::Error: fmt::Debug,
+{
+    /// Returns the number of elements in the `FlatSlice`.
+    #[inline]
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.ends.len()
+    }
+
+    /// Returns true if the len is 0.
+    #[inline]
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.ends.is_empty()
+    }
+
+    // Returns the offset in data where the index'th element starts, which is also the end of the
+    // previous element
+    #[inline]
+    fn start_of(&self, index: usize) -> usize {
+        self.offsets().start_of(index)
+    }
+
+    #[inline]
+    fn offsets(&self) -> Offsets<'a, IndexTy> {
+        Offsets {
+            ends: self.ends,
+            start: self.start,
+        }
+    }
+
+    /// Returns the range of the `FlatVec`'s backing storage occupied by the elements in the
+    /// `FlatSlice`.
+    #[inline]
+    #[must_use]
+    pub fn data_range(&self) -> Range<usize> {
+        self.start..self.start_of(self.len())
+    }
+
+    /// Construct a `Dest` from the `index`th element's stored representation.
+    #[inline]
+    #[must_use]
+    pub fn get<Dest>(&self, index: usize) -> Option<Dest>
+    where
+        Dest: FromFlat<'a, BackingTy, T> + 'a,
+    {
+        self.raw_get(index).map(Dest::from_flat)
+    }
+
+    /// Returns the stored representation of the `index`th element.
+    #[inline]
+    #[must_use]
+    pub fn raw_get(&self, index: usize) -> Option<&'a [BackingTy]> {
+        if index >= self.len() {
+            None
+        } else {
+            Some(&self.data[self.offsets().range_of(index)])
+        }
+    }
+
+    /// Returns an iterator that constructs a `Dest` from each element's stored representation.
+    #[inline]
+    pub fn iter<Dest>(&self) -> Iter<'a, T, Dest, IndexTy, BackingTy>
+    where
+        Dest: FromFlat<'a, BackingTy, T> + 'a,
+    {
+        Iter {
+            raw: self.raw_iter(),
+            marker: PhantomData,
+        }
+    }
+
+    /// Returns an iterator over the stored representation of each element.
+    #[inline]
+    pub fn raw_iter(&self) -> RawIter<'a, IndexTy, BackingTy> {
+        RawIter {
+            data: self.data,
+            offsets: self.offsets(),
+        }
+    }
+
+    /// Returns a view of the elements in `range`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the range is out of bounds, like slice indexing.
+    #[inline]
+    #[must_use]
+    pub fn slice<R>(&self, range: R) -> Self
+    where
+        R: RangeBounds<usize>,
+    {
+        let range = resolve_range(range, self.len());
+        FlatSlice {
+            data: self.data,
+            ends: &self.ends[range.clone()],
+            start: self.start_of(range.start),
+            marker: PhantomData,
+        }
+    }
+
+    /// Divides the `FlatSlice` into two at an index. The first contains the elements in
+    /// `0..mid` and the second contains the elements in `mid..len`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `mid > len`.
+    #[inline]
+    #[must_use]
+    pub fn split_at(&self, mid: usize) -> (Self, Self) {
+        assert!(
+            mid <= self.len(),
+            "split_at index (is {}) should be <= len (is {})",
+            mid,
+            self.len()
+        );
+        let (left, right) = self.ends.split_at(mid);
+        let left = FlatSlice {
+            data: self.data,
+            ends: left,
+            start: self.start,
+            marker: PhantomData,
+        };
+        let right = FlatSlice {
+            data: self.data,
+            ends: right,
+            start: self.start_of(mid),
+            marker: PhantomData,
+        };
+        (left, right)
+    }
+
+    /// Construct a `Dest` from the first element's stored representation, and return it with the
+    /// rest of the elements, or return `None` if the `FlatSlice` is empty.
+    #[inline]
+    #[must_use]
+    pub fn split_first<Dest>(&self) -> Option<(Dest, Self)>
+    where
+        Dest: FromFlat<'a, BackingTy, T> + 'a,
+    {
+        let first = self.get(0)?;
+        Some((first, self.split_at(1).1))
+    }
+
+    /// Construct a `Dest` from the last element's stored representation, and return it with the
+    /// rest of the elements, or return `None` if the `FlatSlice` is empty.
+    #[inline]
+    #[must_use]
+    pub fn split_last<Dest>(&self) -> Option<(Dest, Self)>
+    where
+        Dest: FromFlat<'a, BackingTy, T> + 'a,
+    {
+        let last = self.len().checked_sub(1)?;
+        Some((self.get(last)?, self.split_at(last).0))
+    }
+}
+
+impl<'a, T: 'a, IndexTy, BackingTy, const INDEX_INLINE_LEN: usize, Data>
+    FlatVec<T, IndexTy, BackingTy, INDEX_INLINE_LEN, Data>
+where
+    IndexTy: Default,
+    IndexTy: TryFrom<usize> + Copy + Sub,
+    usize: TryFrom<IndexTy>,
+    <IndexTy as TryFrom<usize>>::Error: fmt::Debug,
+    <usize as TryFrom<IndexTy>>::Error: fmt::Debug,
+    Data: AsRef<[BackingTy]>,
+{
+    /// Returns a borrowed view of all the elements.
+    #[inline]
+    #[must_use]
+    pub fn as_slice(&self) -> FlatSlice<'_, T, IndexTy, BackingTy> {
+        FlatSlice {
+            data: self.data.as_ref(),
+            ends: &self.ends,
+            start: 0,
+            marker: PhantomData,
+        }
+    }
+
+    /// Returns a borrowed view of the elements in `range`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the range is out of bounds, like slice indexing.
+    #[inline]
+    #[must_use]
+    pub fn slice<R>(&self, range: R) -> FlatSlice<'_, T, IndexTy, BackingTy>
+    where
+        R: RangeBounds<usize>,
+    {
+        self.as_slice().slice(range)
+    }
+
+    /// Divides the `FlatVec` into two borrowed views at an index, without copying. The first
+    /// contains the elements in `0..mid` and the second contains the elements in `mid..len`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `mid > len`.
+    #[inline]
+    #[must_use]
+    pub fn split_at(
+        &self,
+        mid: usize,
+    ) -> (
+        FlatSlice<'_, T, IndexTy, BackingTy>,
+        FlatSlice<'_, T, IndexTy, BackingTy>,
+    ) {
+        self.as_slice().split_at(mid)
+    }
+
+    /// Construct a `Dest` from the first element's stored representation, and return it with a
+    /// borrowed view of the rest of the elements, or return `None` if the `FlatVec` is empty.
+    #[inline]
+    #[must_use]
+    pub fn split_first<Dest>(&'a self) -> Option<(Dest, FlatSlice<'a, T, IndexTy, BackingTy>)>
+    where
+        Dest: FromFlat<'a, BackingTy, T> + 'a,
+    {
+        self.as_slice().split_first()
+    }
+
+    /// Construct a `Dest` from the last element's stored representation, and return it with a
+    /// borrowed view of the rest of the elements, or return `None` if the `FlatVec` is empty.
+    #[inline]
+    #[must_use]
+    pub fn split_last<Dest>(&'a self) -> Option<(Dest, FlatSlice<'a, T, IndexTy, BackingTy>)>
+    where
+        Dest: FromFlat<'a, BackingTy, T> + 'a,
+    {
+        self.as_slice().split_last()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::FlatVec;
+    use alloc::{string::String, vec::Vec};
+
+    #[test]
+    fn split_at() {
+        let places: FlatVec<String, usize, u8, 3> = ["Cyador", "Recluce", "Hamor", "Sarronnyn"]
+            .iter()
+            .copied()
+            .collect();
+        let (left, right) = places.split_at(1);
+        assert_eq!((left.len(), right.len()), (1, 3));
+        assert_eq!(left.get(0), Some("Cyador"));
+        assert_eq!(left.get::<&str>(1), None);
+        assert_eq!(right.get(0), Some("Recluce"));
+        assert_eq!(right.raw_get(2), Some(&b"Sarronnyn"[..]));
+        assert_eq!(right.data_range(), 6..27);
+
+        let (middle, last) = right.split_at(2);
+        assert_eq!(middle.get(1), Some("Hamor"));
+        assert_eq!(last.get(0), Some("Sarronnyn"));
+        assert!(places.split_at(4).1.is_empty());
+        assert!(places.split_at(0).0.is_empty());
+    }
+
+    #[test]
+    fn slice() {
+        let places: FlatVec<String, usize, u8, 3> = ["Cyador", "Recluce", "Hamor", "Sarronnyn"]
+            .iter()
+            .copied()
+            .collect();
+        let all = places.as_slice();
+        assert_eq!(all.len(), 4);
+        assert_eq!(all.iter().collect::<Vec<&str>>(), places.to_vec::<&str>());
+
+        let middle = places.slice(1..3);
+        assert_eq!(middle.iter().collect::<Vec<&str>>(), ["Recluce", "Hamor"]);
+        assert_eq!(
+            middle.raw_iter().rev().collect::<Vec<_>>(),
+            [&b"Hamor"[..], b"Recluce"]
+        );
+        assert_eq!(middle.slice(1..).get(0), Some("Hamor"));
+        assert!(places.slice(4..).is_empty());
+        assert_eq!(places.slice(..0).iter::<&str>().count(), 0);
+    }
+
+    #[test]
+    #[should_panic]
+    fn slice_out_of_bounds() {
+        let places: FlatVec<String, usize, u8, 3> = ["Cyador"].iter().copied().collect();
+        let _ = places.slice(..2);
+    }
+
+    #[test]
+    #[should_panic]
+    fn split_at_out_of_bounds() {
+        let places: FlatVec<String, usize, u8, 3> = ["Cyador"].iter().copied().collect();
+        let _ = places.split_at(2);
+    }
+
+    #[test]
+    fn split_first_last() {
+        let places: FlatVec<String, usize, u8, 3> =
+            ["Cyador", "Recluce", "Hamor"].iter().copied().collect();
+        let (first, rest) = places.split_first::<&str>().unwrap();
+        assert_eq!(first, "Cyador");
+        let (last, rest) = rest.split_last::<&str>().unwrap();
+        assert_eq!(last, "Hamor");
+        assert_eq!(rest.len(), 1);
+        assert_eq!(rest.get(0), Some("Recluce"));
+
+        let (last, _) = places.split_last::<&str>().unwrap();
+        assert_eq!(last, "Hamor");
+        assert!(FlatVec::<String, usize, u8, 3>::new()
+            .split_first::<&str>()
+            .is_none());
+    }
+}
@@ -0,0 +1,256 @@
+use crate::{FlatVec, FromFlat, IntoFlat};
+use alloc::vec::Vec;
+use core::{convert::TryFrom, fmt, mem, ops::Sub};
+
+/// Identifies an element of a `HandleVec`, returned by `HandleVec::push`.
+///
+/// A handle stays valid until its element is removed, no matter which other elements are
+/// removed. After that, it never refers to any element again.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Handle {
+    slot: usize,
+    generation: u32,
+}
+
+#[derive(Clone, Debug)]
+struct Slot {
+    // The current index of this slot's element in the FlatVec, or None if the slot is free
+    index: Option<usize>,
+    generation: u32,
+}
+
+/// A `FlatVec` whose elements are identified by `Handle`s instead of their indices, so that
+/// removing an element does not invalidate the handles of other elements.
+///
+/// The elements are stored densely in a `FlatVec`, in an unspecified order. Removing an element
+/// moves the last element into its place, as with `FlatVec::swap_remove`, and updates the table
+/// which maps each handle to the current index of its element.
+#[derive(Clone, Debug)]
+pub struct HandleVec<T, IndexTy: Default, BackingTy, const INDEX_INLINE_LEN: usize> {
+    vec: FlatVec<T, IndexTy, BackingTy, INDEX_INLINE_LEN>,
+    slots: Vec<Slot>,
+    // The slot of each element in the FlatVec
+    owners: Vec<usize>,
+    free: Vec<usize>,
+}
+
+impl<T, IndexTy, BackingTy, const INDEX_INLINE_LEN: usize> Default
+    for HandleVec<T, IndexTy, BackingTy, INDEX_INLINE_LEN>
+where
+    IndexTy: Default,
+{
+    #[inline]
+    fn default() -> Self {
+        Self {
+            vec: FlatVec::default(),
+            slots: Vec::new(),
+            owners: Vec::new(),
+            free: Vec::new(),
+        }
+    }
+}
+
+impl<'a, T: 'a, IndexTy, BackingTy, const INDEX_INLINE_LEN: usize>
+    HandleVec<T, IndexTy, BackingTy, INDEX_INLINE_LEN>
+where
+    IndexTy: Default,
+    IndexTy: TryFrom<usize> + Copy + Sub,
+    usize: TryFrom<IndexTy>,
+    <IndexTy as TryFrom<usize>>::Error: fmt::Debug,
+    <usize as TryFrom<IndexTy>>::Error: fmt::Debug,
+{
+    /// Create a new `HandleVec`, this is just an alias for the `Default` implementation.
+    #[inline]
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns the number of elements in the `HandleVec`.
+    #[inline]
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.vec.len()
+    }
+
+    /// Returns true if the len is 0.
+    #[inline]
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.vec.is_empty()
+    }
+
+    /// Returns the dense `FlatVec` which stores the elements.
+    #[inline]
+    #[must_use]
+    pub fn as_flat_vec(&self) -> &FlatVec<T, IndexTy, BackingTy, INDEX_INLINE_LEN> {
+        &self.vec
+    }
+
+    // Returns the current index of the element identified by handle, if it has not been removed
+    #[inline]
+    fn index_of(&self, handle: Handle) -> Option<usize> {
+        match self.slots.get(handle.slot) {
+            Some(slot) if slot.generation == handle.generation => slot.index,
+            _ => None,
+        }
+    }
+
+    /// Returns true if the element identified by `handle` has not been removed.
+    #[inline]
+    #[must_use]
+    pub fn contains(&self, handle: Handle) -> bool {
+        self.index_of(handle).is_some()
+    }
+
+    /// Appends an element and returns the handle which identifies it.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the new `data_len` cannot be represented as an `IndexTy`.
+    #[inline]
+    pub fn push<Source>(&mut self, input: Source) -> Handle
+    where
+        Source: IntoFlat<BackingTy, T>,
+    {
+        self.vec.push(input);
+        let index = Some(self.vec.len() - 1);
+        let slot = match self.free.pop() {
+            Some(slot) => {
+                self.slots[slot].index = index;
+                slot
+            }
+            None => {
+                self.slots.push(Slot {
+                    index,
+                    generation: 0,
+                });
+                self.slots.len() - 1
+            }
+        };
+        self.owners.push(slot);
+        Handle {
+            slot,
+            generation: self.slots[slot].generation,
+        }
+    }
+
+    /// Construct a `Dest` from the stored representation of the element identified by `handle`,
+    /// or return `None` if it has been removed.
+    #[inline]
+    #[must_use]
+    pub fn get<Dest>(&'a self, handle: Handle) -> Option<Dest>
+    where
+        Dest: FromFlat<'a, BackingTy, T> + 'a,
+    {
+        self.vec.get(self.index_of(handle)?)
+    }
+
+    /// Returns the stored representation of the element identified by `handle`, or `None` if it
+    /// has been removed.
+    #[inline]
+    #[must_use]
+    pub fn raw_get(&self, handle: Handle) -> Option<&[BackingTy]> {
+        self.vec.raw_get(self.index_of(handle)?)
+    }
+
+    /// Removes the element identified by `handle`, and returns whether it was present.
+    ///
+    /// This function is `O(self.data_len())` in the worst case, see `FlatVec::swap_remove`.
+    #[inline]
+    pub fn remove(&mut self, handle: Handle) -> bool {
+        let index = match self.index_of(handle) {
+            Some(index) => index,
+            None => return false,
+        };
+        self.vec.swap_remove(index);
+        self.owners.swap_remove(index);
+        if let Some(&moved) = self.owners.get(index) {
+            self.slots[moved].index = Some(index);
+        }
+        self.vacate(handle.slot);
+        true
+    }
+
+    // Marks a slot as free, and invalidates the handles which refer to it
+    fn vacate(&mut self, slot: usize) {
+        let slot_data = &mut self.slots[slot];
+        slot_data.index = None;
+        // If the generation is exhausted the slot is retired instead of reused, so that stale
+        // handles can never match it again
+        if let Some(generation) = slot_data.generation.checked_add(1) {
+            slot_data.generation = generation;
+            self.free.push(slot);
+        }
+    }
+
+    /// Returns an iterator over the handle and stored representation of each element, in an
+    /// unspecified order.
+    #[inline]
+    pub fn iter_raw(&self) -> impl Iterator<Item = (Handle, &[BackingTy])> + '_ {
+        self.owners
+            .iter()
+            .zip(self.vec.raw_iter())
+            .map(move |(&slot, data)| {
+                let handle = Handle {
+                    slot,
+                    generation: self.slots[slot].generation,
+                };
+                (handle, data)
+            })
+    }
+
+    /// Removes all elements. Every handle which was returned before this call is invalidated.
+    #[inline]
+    pub fn clear(&mut self) {
+        for slot in mem::take(&mut self.owners) {
+            self.vacate(slot);
+        }
+        self.vec.clear();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::HandleVec;
+    use alloc::{string::String, vec::Vec};
+
+    #[test]
+    fn handles() {
+        let mut names: HandleVec<String, usize, u8, 3> = HandleVec::new();
+        let cerryl = names.push("Cerryl");
+        let anya = names.push("Anya");
+        let jeslek = names.push("Jeslek");
+
+        assert!(names.remove(cerryl));
+        assert!(!names.remove(cerryl));
+        assert_eq!(names.len(), 2);
+        assert_eq!(names.get::<&str>(cerryl), None);
+        assert_eq!(names.get(anya), Some("Anya"));
+        assert_eq!(names.get(jeslek), Some("Jeslek"));
+
+        // The freed slot is reused, but the stale handle does not refer to the new element
+        let gorsuch = names.push("Gorsuch");
+        assert_eq!(names.get::<&str>(cerryl), None);
+        assert_eq!(names.raw_get(gorsuch), Some(&b"Gorsuch"[..]));
+
+        let mut all = names
+            .iter_raw()
+            .map(|(handle, data)| (names.get::<&str>(handle).unwrap(), data))
+            .collect::<Vec<_>>();
+        all.sort();
+        assert_eq!(
+            all,
+            [
+                ("Anya", &b"Anya"[..]),
+                ("Gorsuch", b"Gorsuch"),
+                ("Jeslek", b"Jeslek")
+            ]
+        );
+
+        names.clear();
+        assert!(names.is_empty());
+        assert!(!names.contains(anya));
+        assert!(names.as_flat_vec().is_empty());
+    }
+}
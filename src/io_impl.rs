@@ -0,0 +1,239 @@
+use crate::{CapacityError, FlatVec, Storage};
+use core::{
+    convert::{TryFrom, TryInto},
+    fmt,
+    ops::Sub,
+};
+use std::{
+    io::{self, Read, Write},
+    vec::Vec,
+};
+use tinyvec::TinyVec;
+
+// Identifies the format, and its version
+const MAGIC: &[u8; 8] = b"flatvec1";
+
+fn invalid_data<E>(error: E) -> io::Error
+where
+    E: Into<std::boxed::Box<dyn std::error::Error + Send + Sync>>,
+{
+    io::Error::new(io::ErrorKind::InvalidData, error)
+}
+
+fn read_u64<R: Read>(r: &mut R) -> io::Result<u64> {
+    let mut bytes = [0; 8];
+    r.read_exact(&mut bytes)?;
+    Ok(u64::from_le_bytes(bytes))
+}
+
+fn read_usize<R: Read>(r: &mut R) -> io::Result<usize> {
+    usize::try_from(read_u64(r)?).map_err(invalid_data)
+}
+
+impl<T, IndexTy, const INDEX_INLINE_LEN: usize> FlatVec<T, IndexTy, u8, INDEX_INLINE_LEN>
+where
+    IndexTy: Default,
+    IndexTy: TryFrom<usize> + Copy + Sub,
+    usize: TryFrom<IndexTy>,
+    <IndexTy as TryFrom<usize>>::Error: fmt::Debug,
+    <usize as TryFrom<IndexTy>>::Error: fmt::Debug,
+{
+    /// Writes the `FlatVec` to `w` in a compact binary format which can be read back with
+    /// `read_from`.
+    ///
+    /// The format is an 8-byte header, the number of elements and `data_len` as little-endian
+    /// `u64`, the end offset of each element as a little-endian `u64`, then the backing storage
+    /// of every element. Spare capacity is not written.
+    #[inline]
+    pub fn write_to<W: Write>(&self, w: &mut W) -> io::Result<()> {
+        w.write_all(MAGIC)?;
+        w.write_all(&(self.len() as u64).to_le_bytes())?;
+        w.write_all(&(self.data_len as u64).to_le_bytes())?;
+        for &end in self.ends.iter() {
+            let end: usize = end.try_into().unwrap();
+            w.write_all(&(end as u64).to_le_bytes())?;
+        }
+        w.write_all(&self.data[..self.data_len])
+    }
+
+    /// Reads a `FlatVec` in the format written by `write_to`.
+    ///
+    /// Returns an error of kind `InvalidData` if the header is not recognized, or if the offsets
+    /// do not describe a valid `FlatVec`, and an error of kind `UnexpectedEof` if `r` ends early.
+    #[inline]
+    pub fn read_from<R: Read>(r: &mut R) -> io::Result<Self> {
+        let mut magic = [0; 8];
+        r.read_exact(&mut magic)?;
+        if &magic != MAGIC {
+            return Err(invalid_data("not a serialized FlatVec"));
+        }
+        let len = read_usize(r)?;
+        let data_len = read_usize(r)?;
+
+        // The lengths are not trusted, so buffers grow as data is actually read
+        let mut ends = TinyVec::<[IndexTy; INDEX_INLINE_LEN]>::new();
+        for _ in 0..len {
+            let end = IndexTy::try_from(read_usize(r)?)
+                .map_err(|_| invalid_data("an end offset cannot be represented as an IndexTy"))?;
+            ends.push(end);
+        }
+        let mut data = Vec::new();
+        r.take(data_len as u64).read_to_end(&mut data)?;
+        if data.len() != data_len {
+            return Err(io::ErrorKind::UnexpectedEof.into());
+        }
+        Self::from_raw_parts(data.into_boxed_slice(), data_len, ends).map_err(invalid_data)
+    }
+
+    /// Appends an element to the back of the collection, which is read from `reader` until it
+    /// reaches end of file.
+    ///
+    /// If `len_hint` is provided, that much backing storage is reserved before reading. If this
+    /// returns an error, including an error of kind `InvalidData` if the new `data_len` cannot
+    /// be represented as an `IndexTy`, the `FlatVec` is left holding exactly the elements it
+    /// held before the call.
+    #[inline]
+    pub fn push_read<R: Read>(&mut self, mut reader: R, len_hint: Option<usize>) -> io::Result<()> {
+        let old_data_len = self.data_len;
+        if let Some(len) = len_hint {
+            self.reserve_data(len);
+        }
+        if let Err(e) = self.read_to_end(&mut reader) {
+            self.data_len = old_data_len;
+            return Err(e);
+        }
+        match IndexTy::try_from(self.data_len) {
+            Ok(end) => {
+                self.ends.push(end);
+                Ok(())
+            }
+            Err(_) => {
+                let offset = self.data_len;
+                self.data_len = old_data_len;
+                Err(invalid_data(CapacityError { offset }))
+            }
+        }
+    }
+
+    // Reads directly into the spare capacity of the backing storage, growing it when it fills
+    fn read_to_end<R: Read>(&mut self, reader: &mut R) -> io::Result<()> {
+        loop {
+            if self.data_len == self.data.len() {
+                self.reserve_data(32);
+            }
+            match reader.read(&mut self.data[self.data_len..]) {
+                Ok(0) => return Ok(()),
+                Ok(n) => self.data_len += n,
+                Err(e) if e.kind() == io::ErrorKind::Interrupted => {}
+                Err(e) => return Err(e),
+            }
+        }
+    }
+}
+
+/// An adapter which implements `io::Write` by inserting everything written into a flattened
+/// object, for `IntoFlat` implementations which use an encoder or serializer.
+pub struct StorageWriter<'a>(pub Storage<'a, u8>);
+
+impl Write for StorageWriter<'_> {
+    #[inline]
+    fn write(&mut self, data: &[u8]) -> io::Result<usize> {
+        self.0.extend_from_slice(data);
+        Ok(data.len())
+    }
+
+    #[inline]
+    fn write_all(&mut self, data: &[u8]) -> io::Result<()> {
+        self.0.extend_from_slice(data);
+        Ok(())
+    }
+
+    #[inline]
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::StorageWriter;
+    use crate::FlatVec;
+    use std::{
+        io::{self, Write},
+        string::String,
+        vec::Vec,
+    };
+
+    #[test]
+    fn storage_writer() {
+        let mut names: FlatVec<String, usize, u8, 3> = FlatVec::new();
+        names.push_with(|storage| {
+            let mut writer = StorageWriter(storage);
+            let (a, b) = ("Cerryl", "Anya");
+            write!(writer, "{} and {}", a, b).unwrap();
+        });
+        assert_eq!(names.get(0), Some("Cerryl and Anya"));
+    }
+
+    #[test]
+    fn push_read() {
+        let mut names: FlatVec<String, u8, u8, 3> = FlatVec::new();
+        names.push_read(&b"Cerryl"[..], None).unwrap();
+        names.push_read(&b"Anya"[..], Some(4)).unwrap();
+        names.push_read(&b""[..], Some(16)).unwrap();
+        assert_eq!(names.iter().collect::<Vec<&str>>(), ["Cerryl", "Anya", ""]);
+
+        let long = [b'a'; 300];
+        let err = names.push_read(&long[..], None).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+        assert_eq!(names.len(), 3);
+        assert_eq!(names.data_len(), 10);
+    }
+
+    #[test]
+    fn round_trip() {
+        let mut names: FlatVec<String, u16, u8, 3> =
+            ["Cerryl", "", "Anya", "Jeslek"].iter().copied().collect();
+        names.reserve_data(100);
+        let mut bytes = Vec::new();
+        names.write_to(&mut bytes).unwrap();
+        assert_eq!(bytes.len(), 8 + 8 + 8 + 4 * 8 + 16);
+
+        let read = FlatVec::<String, u16, u8, 3>::read_from(&mut &bytes[..]).unwrap();
+        assert_eq!(read, names);
+        assert_eq!(read.data_capacity(), 16);
+    }
+
+    #[test]
+    fn reject_invalid() {
+        let names: FlatVec<String, u16, u8, 3> = ["Cerryl", "Anya"].iter().copied().collect();
+        let mut bytes = Vec::new();
+        names.write_to(&mut bytes).unwrap();
+        let read = |bytes: &[u8]| FlatVec::<String, u16, u8, 3>::read_from(&mut &bytes[..]);
+
+        let truncated = read(&bytes[..bytes.len() - 1]).unwrap_err();
+        assert_eq!(truncated.kind(), io::ErrorKind::UnexpectedEof);
+
+        let mut bad_magic = bytes.clone();
+        bad_magic[0] = b'F';
+        assert_eq!(
+            read(&bad_magic).unwrap_err().kind(),
+            io::ErrorKind::InvalidData
+        );
+
+        // The first end offset is the 4th u64
+        let mut unsorted = bytes.clone();
+        unsorted[24] = 11;
+        assert_eq!(
+            read(&unsorted).unwrap_err().kind(),
+            io::ErrorKind::InvalidData
+        );
+
+        let mut too_large = bytes;
+        too_large[24..32].copy_from_slice(&(1u64 << 16).to_le_bytes());
+        assert_eq!(
+            read(&too_large).unwrap_err().kind(),
+            io::ErrorKind::InvalidData
+        );
+    }
+}
@@ -0,0 +1,57 @@
+use crate::{FlatVec, FromFlat};
+use core::{convert::TryFrom, fmt, ops::Sub};
+use serde::Serialize;
+use std::io::Write;
+
+impl<'a, T: 'a, IndexTy, BackingTy, const INDEX_INLINE_LEN: usize, Data>
+    FlatVec<T, IndexTy, BackingTy, INDEX_INLINE_LEN, Data>
+where
+    IndexTy: Default,
+    IndexTy: TryFrom<usize> + Copy + Sub,
+    usize: TryFrom<IndexTy>,
+    <IndexTy as TryFrom<usize>>::Error: fmt::Debug,
+    <usize as TryFrom<IndexTy>>::Error: fmt::Debug,
+    Data: AsRef<[BackingTy]>,
+{
+    /// Writes a JSON array to `w`, which contains a `Dest` constructed from each element's stored
+    /// representation.
+    ///
+    /// Each element is serialized as soon as it is constructed, so the elements are never all
+    /// alive at once.
+    #[inline]
+    pub fn write_json_array<Dest, W>(&'a self, w: &mut W) -> serde_json::Result<()>
+    where
+        Dest: FromFlat<'a, BackingTy, T> + Serialize + 'a,
+        W: Write,
+    {
+        w.write_all(b"[").map_err(serde_json::Error::io)?;
+        for (index, element) in self.iter::<Dest>().enumerate() {
+            if index > 0 {
+                w.write_all(b",").map_err(serde_json::Error::io)?;
+            }
+            serde_json::to_writer(&mut *w, &element)?;
+        }
+        w.write_all(b"]").map_err(serde_json::Error::io)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::FlatVec;
+    use std::{string::String, vec::Vec};
+
+    #[test]
+    fn write_json_array() {
+        let names: FlatVec<String, usize, u8, 3> =
+            ["Cerryl", "", "An\"ya"].iter().copied().collect();
+        let mut out = Vec::new();
+        names.write_json_array::<&str, _>(&mut out).unwrap();
+        assert_eq!(out, br#"["Cerryl","","An\"ya"]"#);
+
+        let mut out = Vec::new();
+        FlatVec::<String, usize, u8, 3>::new()
+            .write_json_array::<&str, _>(&mut out)
+            .unwrap();
+        assert_eq!(out, b"[]");
+    }
+}
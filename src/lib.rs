@@ -1,7 +1,12 @@
 //! An indirection-collapsing container that generalizes [nested](https://crates.io/crates/nested).
 //!
 //! A `FlatVec` can be used like a `Vec<String>` or `Vec<Vec<u8>>`, but with a maximum of 2 heap
-//! allocations instead of n + 1 (currently it's always 2 but that should change with 1.51).
+//! allocations instead of n + 1. The end offsets of the first `INDEX_INLINE_LEN` elements are
+//! stored inline, so a `FlatVec` with at most that many elements has only the single allocation
+//! for its backing storage; `shrink_to_fit` moves the end offsets back inline when they fit.
+//! Once a `FlatVec` with an integer `BackingTy` is done being built, `FlatVec::into_compact`
+//! converts it into a read-only `CompactFlatVec`, which stores the end offsets at the tail of the
+//! backing storage so that it has a single allocation no matter how many elements it has.
 //!
 //! Insertion into and retrieval from a `FlatVec` is mediated by two traits, `IntoFlat` and
 //! `FromFlat`, which are both parameterized on two types. The simplest way to use this crate is to
@@ -14,6 +19,9 @@
 //! ```rust
 //! pub type FlatVec<T> = flatvec::FlatVec<T, usize, u8, 3>;
 //! ```
+//! Slices of integers wider than a byte can be stored without any packing in a matching backing
+//! type, with `Box<[u32]>` as the element type of a `FlatVec<Box<[u32]>, usize, u32, 3>` and
+//! likewise for `u16`, `u64` and their signed counterparts.
 //!
 //! Additionally, since `FromFlat` has a lifetime parameter, accessing the stored objects in a
 //! `FlatVec` can be a zero-copy operation. For example, one may flatten objects with indirections
@@ -23,18 +31,80 @@
 //! This interface is extremely powerful and essentially amounts to in-memory serialization and
 //! conversion all in one. For example, a user can construct a `FlatVec` that compresses all of its
 //! elements with gzip. This is not necessarily a good idea, but you can do it.
+//!
+//! Writing `IntoFlat` and `FromFlat` implementations by hand for simple structs is repetitive, so
+//! the `derive` feature provides `#[derive(Flatten)]`, which is demonstrated in
+//! `examples/derive.rs`.
+//!
+//! This crate is `no_std` and only requires `alloc`. The default `std` feature enables
+//! integration with `std`, such as implementing `std::error::Error` for the error types.
 
+#![no_std]
 #![forbid(unsafe_code)]
 
+extern crate alloc;
+#[cfg(any(feature = "std", test))]
+extern crate std;
+
+use alloc::{
+    borrow::Cow, boxed::Box, collections::TryReserveError, string::String, sync::Arc, vec::Vec,
+};
 use core::{
+    array,
+    cmp::{self, Ordering},
     convert::{TryFrom, TryInto},
-    fmt, iter,
+    fmt,
+    hash::{Hash, Hasher},
+    iter,
     marker::PhantomData,
-    ops::Sub,
-    str,
+    mem,
+    ops::{self, Bound, Range, RangeBounds, Sub},
+    ptr, str,
 };
 use tinyvec::TinyVec;
 
+pub use tinyvec;
+
+#[cfg(feature = "derive")]
+pub use flatvec_derive::Flatten;
+
+#[cfg(feature = "arbitrary")]
+mod arbitrary_impl;
+#[cfg(feature = "bytemuck")]
+mod bytemuck_impl;
+mod compact;
+pub use compact::{CompactFlatVec, CompactIter, CompactRawIter};
+mod endian;
+pub use endian::FlatReader;
+mod flat_slice;
+pub use flat_slice::FlatSlice;
+mod handle_vec;
+pub use handle_vec::{Handle, HandleVec};
+#[cfg(feature = "std")]
+mod io_impl;
+#[cfg(feature = "std")]
+pub use io_impl::StorageWriter;
+#[cfg(feature = "json")]
+mod json_impl;
+#[cfg(feature = "proptest")]
+pub mod proptest;
+#[cfg(feature = "rayon")]
+mod rayon_impl;
+#[cfg(feature = "serde")]
+mod serde_impl;
+
+/// Re-exports the items needed to use a `FlatVec` and implement its traits, for glob importing
+/// with `use flatvec::prelude::*;`
+pub mod prelude {
+    #[cfg(feature = "derive")]
+    pub use crate::Flatten;
+    pub use crate::{
+        ByteVec, FlatReader, FlatVec, FromFlat, FromFlatInto, IntoFlat, Storage, TryFromFlat,
+    };
+}
+
+pub(crate) const DEFAULT_GROWTH_FACTOR: f32 = 2.0;
+
 /// An indirection-collapsing container with minimal allocation
 ///
 /// Read as "An internally-flattening Vec of T, indexed by `IndexTy`, where each `T` is stored as a
@@ -42,28 +112,64 @@ use tinyvec::TinyVec;
 /// For simple use cases, you may want a type alias for `FlatVec<T, usize, u8, 3>`, but under some
 /// workloads it is very profitable to pick a smaller `IndexTy`, possibly even `u8`, and a
 /// corresponding inline capacity.
+///
+/// The backing storage is a `Box<[BackingTy]>` by default, which is required for all the methods
+/// that add elements or otherwise change the backing storage. But any `Data: AsRef<[BackingTy]>`,
+/// such as a `&[BackingTy]` or a memory map, can be used as the backing storage of a `FlatVec`
+/// which is only read from, see `FlatVec::from_data`.
+///
+/// Every way of building a `FlatVec` from end offsets which were not produced by the `FlatVec`
+/// itself, such as `from_raw_parts`, `from_backing`, `from_data`, `read_from`, or
+/// deserialization, checks that the offsets are valid and returns an error if they are not. So a
+/// malformed layout is rejected up front, and methods such as `get` and `iter` never panic because
+/// of one. `try_raw_get` and `try_iter` check the end offsets again as they read them, and return
+/// a `LayoutError` instead of panicking.
 #[derive(Clone)]
-pub struct FlatVec<T, IndexTy: Default, BackingTy, const INDEX_INLINE_LEN: usize> {
-    data: Box<[BackingTy]>,
+pub struct FlatVec<
+    T,
+    IndexTy: Default,
+    BackingTy,
+    const INDEX_INLINE_LEN: usize,
+    Data = Box<[BackingTy]>,
+> {
+    data: Data,
     data_len: usize,
     ends: TinyVec<[IndexTy; INDEX_INLINE_LEN]>,
-    marker: PhantomData<T>,
+    growth_factor: f32,
+    marker: PhantomData<(T, BackingTy)>,
 }
 
-impl<T, IndexTy, BackingTy, const INDEX_INLINE_LEN: usize> fmt::Debug
-    for FlatVec<T, IndexTy, BackingTy, INDEX_INLINE_LEN>
+/// A `FlatVec` with the most common configuration, which stores each `T` as bytes.
+pub type ByteVec<T> = FlatVec<T, usize, u8, 3>;
+
+impl<T, IndexTy, BackingTy, const INDEX_INLINE_LEN: usize, Data> fmt::Debug
+    for FlatVec<T, IndexTy, BackingTy, INDEX_INLINE_LEN, Data>
 where
     IndexTy: fmt::Debug + Default,
     BackingTy: fmt::Debug,
+    Data: AsRef<[BackingTy]>,
 {
     fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
         fmt.debug_struct("FlatVec")
-            .field("data", &&self.data[..self.data_len])
+            .field("data", &&self.data.as_ref()[..self.data_len])
             .field("ends", &self.ends)
             .finish()
     }
 }
 
+impl<T, IndexTy, BackingTy, const INDEX_INLINE_LEN: usize, Data>
+    FlatVec<T, IndexTy, BackingTy, INDEX_INLINE_LEN, Data>
+where
+    IndexTy: Default,
+{
+    // Every constructor evaluates this, so that an INDEX_INLINE_LEN of 0, which would store every
+    // end offset on the heap, is a compile error
+    pub(crate) const INLINE_LEN_IS_NONZERO: () = assert!(
+        INDEX_INLINE_LEN > 0,
+        "INDEX_INLINE_LEN must be greater than 0"
+    );
+}
+
 impl<T, IndexTy, BackingTy, const INDEX_INLINE_LEN: usize> Default
     for FlatVec<T, IndexTy, BackingTy, INDEX_INLINE_LEN>
 where
@@ -71,326 +177,4014 @@ where
 {
     #[inline]
     fn default() -> Self {
+        let () = Self::INLINE_LEN_IS_NONZERO;
         Self {
             data: Box::default(),
             data_len: 0,
             ends: TinyVec::default(),
-            marker: PhantomData::default(),
+            growth_factor: DEFAULT_GROWTH_FACTOR,
+            marker: PhantomData,
         }
     }
 }
 
-impl<'a, T: 'a, IndexTy, BackingTy, const INDEX_INLINE_LEN: usize>
-    FlatVec<T, IndexTy, BackingTy, INDEX_INLINE_LEN>
+/// Two `FlatVec`s are equal if they contain the same number of elements and each pair of
+/// elements has an equal stored representation. Spare capacity is not compared.
+impl<T, IndexTy, BackingTy, const INDEX_INLINE_LEN: usize, Data> PartialEq
+    for FlatVec<T, IndexTy, BackingTy, INDEX_INLINE_LEN, Data>
 where
     IndexTy: Default,
     IndexTy: TryFrom<usize> + Copy + Sub,
     usize: TryFrom<IndexTy>,
     <IndexTy as TryFrom<usize>>::Error: fmt::Debug,
     <usize as TryFrom<IndexTy>>::Error: fmt::Debug,
+    Data: AsRef<[BackingTy]>,
+    BackingTy: PartialEq,
 {
-    /// Create a new `FlatVec`, this is just an alias for the `Default` implementation.
-    #[inline]
-    #[must_use]
-    pub fn new() -> Self {
-        Self::default()
-    }
-
-    /// Returns the number of `T` in a `FlatVec<T>`.
     #[inline]
-    #[must_use]
-    pub fn len(&self) -> usize {
-        self.ends.len()
+    fn eq(&self, other: &Self) -> bool {
+        self.len() == other.len() && self.raw_iter().eq(other.raw_iter())
     }
+}
 
-    /// Returns the number of `BackingTy` used to store the elements of a `FlatVec`. This does not
-    /// necessarily correlate with storage used to store the indices.
-    #[inline]
-    #[must_use]
-    pub fn data_len(&self) -> usize {
-        self.data_len
-    }
+impl<T, IndexTy, BackingTy, const INDEX_INLINE_LEN: usize, Data> Eq
+    for FlatVec<T, IndexTy, BackingTy, INDEX_INLINE_LEN, Data>
+where
+    IndexTy: Default,
+    IndexTy: TryFrom<usize> + Copy + Sub,
+    usize: TryFrom<IndexTy>,
+    <IndexTy as TryFrom<usize>>::Error: fmt::Debug,
+    <usize as TryFrom<IndexTy>>::Error: fmt::Debug,
+    Data: AsRef<[BackingTy]>,
+    BackingTy: Eq,
+{
+}
 
+/// `FlatVec`s are compared lexicographically element-by-element, by the stored representation of
+/// each element. Elements are compared as slices, so an element which is a prefix of another
+/// element is less than it. This is consistent with `PartialEq`.
+impl<T, IndexTy, BackingTy, const INDEX_INLINE_LEN: usize, Data> PartialOrd
+    for FlatVec<T, IndexTy, BackingTy, INDEX_INLINE_LEN, Data>
+where
+    IndexTy: Default,
+    IndexTy: TryFrom<usize> + Copy + Sub,
+    usize: TryFrom<IndexTy>,
+    <IndexTy as TryFrom<usize>>::Error: fmt::Debug,
+    <usize as TryFrom<IndexTy>>::Error: fmt::Debug,
+    Data: AsRef<[BackingTy]>,
+    BackingTy: PartialOrd,
+{
     #[inline]
-    #[must_use]
-    pub fn data_capacity(&self) -> usize {
-        self.data.len()
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        self.raw_iter().partial_cmp(other.raw_iter())
     }
+}
 
-    /// Returns true if the len is 0.
+impl<T, IndexTy, BackingTy, const INDEX_INLINE_LEN: usize, Data> Ord
+    for FlatVec<T, IndexTy, BackingTy, INDEX_INLINE_LEN, Data>
+where
+    IndexTy: Default,
+    IndexTy: TryFrom<usize> + Copy + Sub,
+    usize: TryFrom<IndexTy>,
+    <IndexTy as TryFrom<usize>>::Error: fmt::Debug,
+    <usize as TryFrom<IndexTy>>::Error: fmt::Debug,
+    Data: AsRef<[BackingTy]>,
+    BackingTy: Ord,
+{
     #[inline]
-    #[must_use]
-    pub fn is_empty(&self) -> bool {
-        self.ends.len() == 0
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.raw_iter().cmp(other.raw_iter())
     }
+}
 
+impl<T, IndexTy, BackingTy, const INDEX_INLINE_LEN: usize, Data> Hash
+    for FlatVec<T, IndexTy, BackingTy, INDEX_INLINE_LEN, Data>
+where
+    IndexTy: Default,
+    IndexTy: TryFrom<usize> + Copy + Sub,
+    usize: TryFrom<IndexTy>,
+    <IndexTy as TryFrom<usize>>::Error: fmt::Debug,
+    <usize as TryFrom<IndexTy>>::Error: fmt::Debug,
+    Data: AsRef<[BackingTy]>,
+    BackingTy: Hash,
+{
     #[inline]
-    pub fn clear(&mut self) {
-        self.data_len = 0;
-        self.ends.clear();
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.len().hash(state);
+        // Hashing a slice includes its length, so element boundaries affect the hash
+        for element in self.raw_iter() {
+            element.hash(state);
+        }
     }
+}
 
-    /// Appends an element to the back of the collection.
+impl<T, IndexTy, BackingTy, Source, const INDEX_INLINE_LEN: usize> iter::FromIterator<Source>
+    for FlatVec<T, IndexTy, BackingTy, INDEX_INLINE_LEN>
+where
+    IndexTy: Default,
+    IndexTy: TryFrom<usize> + Copy + Sub,
+    usize: TryFrom<IndexTy>,
+    <IndexTy as TryFrom<usize>>::Error: fmt::Debug,
+    <usize as TryFrom<IndexTy>>::Error: fmt::Debug,
+    Source: IntoFlat<BackingTy, T>,
+{
     #[inline]
-    pub fn push<Source>(&mut self, input: Source)
+    fn from_iter<Iter>(iter: Iter) -> Self
     where
-        Source: IntoFlat<BackingTy, T>,
+        Iter: IntoIterator<Item = Source>,
     {
-        input.into_flat(Storage {
-            data: &mut self.data,
-            data_len: &mut self.data_len,
-        });
-        self.ends.push(self.data_len.try_into().unwrap());
+        let mut vec = Self::new();
+        vec.extend(iter);
+        vec
     }
+}
 
-    /// Construct a `Dest` from the `index`th element's stored representation.
+impl<T, IndexTy, BackingTy, Source, const INDEX_INLINE_LEN: usize> Extend<Source>
+    for FlatVec<T, IndexTy, BackingTy, INDEX_INLINE_LEN>
+where
+    IndexTy: Default,
+    IndexTy: TryFrom<usize> + Copy + Sub,
+    usize: TryFrom<IndexTy>,
+    <IndexTy as TryFrom<usize>>::Error: fmt::Debug,
+    <usize as TryFrom<IndexTy>>::Error: fmt::Debug,
+    Source: IntoFlat<BackingTy, T>,
+{
     #[inline]
-    #[must_use]
-    pub fn get<Dest: 'a>(&'a self, index: usize) -> Option<Dest>
+    fn extend<Iter>(&mut self, iter: Iter)
     where
-        Dest: FromFlat<'a, BackingTy, T>,
+        Iter: IntoIterator<Item = Source>,
     {
-        if index >= self.ends.len() {
-            None
-        } else {
-            let end = self.ends[index].try_into().unwrap();
-            let start = if index == 0 {
-                0
-            } else {
-                self.ends[index - 1].try_into().unwrap()
-            };
-            Some(Dest::from_flat(&self.data[start..end]))
+        let iter = iter.into_iter();
+        self.ends.reserve(iter.size_hint().0);
+        for input in iter {
+            self.push(input);
         }
     }
+}
 
-    /// Returns an iterator that constructs a `Dest` from each element's stored representation.
+impl<IndexTy, const INDEX_INLINE_LEN: usize> From<&[&str]>
+    for FlatVec<String, IndexTy, u8, INDEX_INLINE_LEN>
+where
+    IndexTy: Default,
+    IndexTy: TryFrom<usize> + Copy + Sub,
+    usize: TryFrom<IndexTy>,
+    <IndexTy as TryFrom<usize>>::Error: fmt::Debug,
+    <usize as TryFrom<IndexTy>>::Error: fmt::Debug,
+{
     #[inline]
-    pub fn iter<Dest: 'a>(&'a self) -> impl Iterator<Item = Dest> + 'a
-    where
-        Dest: FromFlat<'a, BackingTy, T>,
-    {
-        iter::once(0)
-            .chain(self.ends.iter().copied().map(|v| v.try_into().unwrap()))
-            .zip(self.ends.iter().copied().map(|v| v.try_into().unwrap()))
-            .map(move |(start, end)| Dest::from_flat(&self.data[start..end]))
+    fn from(strings: &[&str]) -> Self {
+        let data_len = strings.iter().map(|s| s.len()).sum();
+        let mut vec = Self::with_capacity(strings.len(), data_len);
+        vec.extend(strings.iter().copied());
+        vec
     }
 }
 
-impl<'a, T: 'a, IndexTy, BackingTy, const INDEX_INLINE_LEN: usize>
-    FlatVec<T, IndexTy, BackingTy, INDEX_INLINE_LEN>
+impl<IndexTy, const INDEX_INLINE_LEN: usize> From<Vec<String>>
+    for FlatVec<String, IndexTy, u8, INDEX_INLINE_LEN>
 where
     IndexTy: Default,
     IndexTy: TryFrom<usize> + Copy + Sub,
     usize: TryFrom<IndexTy>,
     <IndexTy as TryFrom<usize>>::Error: fmt::Debug,
     <usize as TryFrom<IndexTy>>::Error: fmt::Debug,
-    BackingTy: Copy,
 {
-    /// Removes the `index`th element of a `FlatVec`.
-    /// This function is `O(self.len() + self.data_len())`.
     #[inline]
-    pub fn remove(&mut self, index: usize) {
-        let end: usize = self.ends[index].try_into().unwrap();
-        let start = if index == 0 {
-            0
-        } else {
-            self.ends[index - 1].try_into().unwrap()
-        };
-        self.data.copy_within(end.., start);
-        self.ends.remove(index);
-        let removed_len = end - start;
-        self.data_len -= removed_len;
-        self.ends.iter_mut().skip(index).for_each(|end| {
-            let change = usize::try_from(*end).unwrap() - removed_len;
-            *end = change.try_into().unwrap();
-        });
+    fn from(strings: Vec<String>) -> Self {
+        let data_len = strings.iter().map(String::len).sum();
+        let mut vec = Self::with_capacity(strings.len(), data_len);
+        vec.extend(strings.iter().map(String::as_str));
+        vec
     }
 }
 
-// On the surface, this Box juggling seems like a re-implementation of std::vec::Vec.
-// The difference is our allocated memory is always default-initialized, so that we can implement
-// Storage::allocate, which returns a slice of BackingTy that is not yet used for an object in the
-// FlatVec. So just like Vec we have a length and capacity, but unlike Vec the objects beyond the
-// container's length are guaranteed to be initialized and thus can be accessed from safe code.
-/// A wrapper over the innards of a `FlatVec` which exposes mutating operations which cannot
-/// corrupt other elements when inserting a new element.
-pub struct Storage<'a, BackingTy> {
-    data: &'a mut Box<[BackingTy]>,
-    data_len: &'a mut usize,
+impl<T, IndexTy, BackingTy, const INDEX_INLINE_LEN: usize, Data> ops::Index<usize>
+    for FlatVec<T, IndexTy, BackingTy, INDEX_INLINE_LEN, Data>
+where
+    IndexTy: Default,
+    IndexTy: TryFrom<usize> + Copy + Sub,
+    usize: TryFrom<IndexTy>,
+    <IndexTy as TryFrom<usize>>::Error: fmt::Debug,
+    <usize as TryFrom<IndexTy>>::Error: fmt::Debug,
+    Data: AsRef<[BackingTy]>,
+{
+    type Output = [BackingTy];
+
+    /// Returns the stored representation of the `index`th element.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `index` is out of bounds.
+    #[inline]
+    fn index(&self, index: usize) -> &[BackingTy] {
+        match self.byte_range(index) {
+            Some(range) => &self.data.as_ref()[range],
+            None => panic!(
+                "index out of bounds: the len is {} but the index is {}",
+                self.len(),
+                index
+            ),
+        }
+    }
 }
 
-impl<BackingTy> Storage<'_, BackingTy>
+impl<T, IndexTy, BackingTy, const INDEX_INLINE_LEN: usize>
+    FlatVec<T, IndexTy, BackingTy, INDEX_INLINE_LEN>
 where
-    BackingTy: Default,
+    IndexTy: Default,
+    IndexTy: TryFrom<usize> + Copy + Sub,
+    usize: TryFrom<IndexTy>,
+    <IndexTy as TryFrom<usize>>::Error: fmt::Debug,
+    <usize as TryFrom<IndexTy>>::Error: fmt::Debug,
 {
-    #[inline(never)]
-    fn allocate_slow_path(&mut self, requested: usize) {
-        let mut data = std::mem::take(self.data).into_vec();
-        data.resize_with(
-            std::cmp::max(requested + data.len(), 2 * data.len()),
-            BackingTy::default,
-        );
-        *self.data = data.into_boxed_slice();
+    /// Create a new `FlatVec`, this is just an alias for the `Default` implementation.
+    #[inline]
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
     }
 
-    /// Returns a `Default` slice of `BackingTy` that will be considered part of this flattened
-    /// object.
-    ///
-    /// Note that even if you do not use part of this slice, the whole slice will be presented to a
-    /// `FromFlat` implementation. This function may be called multiple times in a single
-    /// `IntoFlat` implementation or combined with `Storage::extend` if a flattened object is
-    /// complex, but it is significantly more efficient to use a single `Storage::allocate` call
-    /// where possible.
+    /// Decomposes a `FlatVec` into its backing storage, the number of `BackingTy` in the backing
+    /// storage which are used by elements, and the end offset of each element.
     #[inline]
-    pub fn allocate(&mut self, requested: usize) -> &mut [BackingTy] {
-        self.reserve(requested);
-        let old_len = *self.data_len;
-        *self.data_len += requested;
-        &mut self.data[old_len..old_len + requested]
+    #[must_use]
+    pub fn into_raw_parts(
+        self,
+    ) -> (
+        Box<[BackingTy]>,
+        usize,
+        TinyVec<[IndexTy; INDEX_INLINE_LEN]>,
+    ) {
+        (self.data, self.data_len, self.ends)
     }
 
-    /// Reserves capacity for at least `len` additional `BackingTy`.
+    /// Creates a `FlatVec` from the parts returned by `into_raw_parts`.
+    ///
+    /// Returns an error if `data_len` is greater than the length of `data`, or if `ends` is not
+    /// sorted or does not end at `data_len`, so that every element of the returned `FlatVec` is
+    /// stored within `data`.
     #[inline]
-    pub fn reserve(&mut self, requested: usize) {
-        if self.data.len() < *self.data_len + requested {
-            self.allocate_slow_path(requested);
-        }
+    pub fn from_raw_parts(
+        data: Box<[BackingTy]>,
+        data_len: usize,
+        ends: TinyVec<[IndexTy; INDEX_INLINE_LEN]>,
+    ) -> Result<Self, LayoutError> {
+        let () = Self::INLINE_LEN_IS_NONZERO;
+        validate_layout(&ends, data_len, data.len())?;
+        Ok(Self {
+            data,
+            data_len,
+            ends,
+            growth_factor: DEFAULT_GROWTH_FACTOR,
+            marker: PhantomData,
+        })
     }
 
-    /// Inserts the `BackingTy` yielded by `iter`.
+    /// Creates a `FlatVec` which uses all of `data` as the backing storage of its elements, where
+    /// `ends` is the end offset of each element.
     ///
-    /// In general, this is ~2x slower than calling `allocate` when the exact size of the inserted
-    /// object is known.
+    /// Returns an error if `ends` is not sorted or does not end at `data.len()`.
+    ///
+    /// The data is copied only if converting it into a `Box<[BackingTy]>` copies it.
     #[inline]
-    pub fn extend<Iter>(&mut self, iter: Iter)
+    pub fn from_backing<Data>(data: Data, ends: Vec<IndexTy>) -> Result<Self, LayoutError>
     where
-        Iter: IntoIterator<Item = BackingTy>,
+        Data: Into<Box<[BackingTy]>>,
     {
-        let mut iter = iter.into_iter();
+        let data = data.into();
+        let data_len = data.len();
+        Self::from_raw_parts(data, data_len, ends.into_iter().collect())
+    }
 
-        // When the iterator provides a precise size hint, use the allocate interface.
-        // This path is ~5x faster than the one below
-        let hint = iter.size_hint();
-        if Some(hint.0) == hint.1 {
-            let data = self.allocate(hint.0);
-            for (src, dst) in iter.into_iter().zip(data.iter_mut()) {
-                *dst = src;
-            }
-            return;
-        }
+    #[inline]
+    #[must_use]
+    pub fn data_capacity(&self) -> usize {
+        self.data.len()
+    }
 
-        // Insert as many elements as possible into already-allocated space
-        for (out, val) in self.data.iter_mut().skip(*self.data_len).zip(&mut iter) {
-            *out = val;
-            *self.data_len += 1;
-        }
+    /// Returns the backing storage of all the elements, in order, for modification in place.
+    ///
+    /// The returned slice cannot be resized, so the boundaries between elements are unaffected.
+    #[inline]
+    #[must_use]
+    pub fn data_as_mut_slice(&mut self) -> &mut [BackingTy] {
+        &mut self.data[..self.data_len]
+    }
 
-        // If there are elements remaining in the iterator, allocate space for them
-        if let Some(val) = iter.next() {
-            let mut data = std::mem::take(self.data).into_vec();
-            data.push(val);
-            *self.data_len += 1;
+    /// Returns the stored representation of the `index`th element for modification in place.
+    ///
+    /// The returned slice cannot be resized, so the length of the element is unaffected.
+    #[inline]
+    #[must_use]
+    pub fn get_mut(&mut self, index: usize) -> Option<&mut [BackingTy]> {
+        self.byte_range(index)
+            .map(move |range| &mut self.data[range])
+    }
 
-            for val in iter {
-                data.push(val);
-                *self.data_len += 1;
-            }
+    /// Returns the stored representation of the last element for modification in place, or
+    /// `None` if the `FlatVec` is empty.
+    #[inline]
+    #[must_use]
+    pub fn last_mut(&mut self) -> Option<&mut [BackingTy]> {
+        self.get_mut(self.len().checked_sub(1)?)
+    }
 
-            if data.capacity() > data.len() {
-                data.resize_with(data.capacity(), BackingTy::default);
-            }
-            let mut data = data.into_boxed_slice();
-            std::mem::swap(self.data, &mut data);
-            std::mem::forget(data);
-        }
+    /// Reserves capacity for at least `additional` more elements to be inserted without
+    /// reallocating the storage for their end offsets.
+    #[inline]
+    pub fn reserve_elements(&mut self, additional: usize) {
+        self.ends.reserve(additional);
     }
-}
 
-/// Implement `IntoFlat<Flattened> for Source` to insert a `Source` into a `FlatVec<Flattened>`
-pub trait IntoFlat<BackingTy, Flattened> {
-    fn into_flat(self, storage: Storage<BackingTy>);
-}
+    /// Returns the factor by which the backing storage grows when it needs to reallocate.
+    #[inline]
+    #[must_use]
+    pub fn growth_factor(&self) -> f32 {
+        self.growth_factor
+    }
 
-/// Implement `FromFlat<'a, Flattened> for Dest` to get a `Dest` from a `FlatVec<Flattened>`
-pub trait FromFlat<'a, BackingTy, Flattened> {
-    fn from_flat(data: &'a [BackingTy]) -> Self;
-}
+    /// Sets the factor by which the backing storage grows when it needs to reallocate, which is
+    /// 2 by default. Smaller factors waste less memory but reallocate more often.
+    ///
+    /// However small the factor, every reallocation leaves at least one element of headroom: the
+    /// backing storage always grows by at least as many `BackingTy` as the element being inserted
+    /// or the reservation needs, so a single insertion never reallocates more than once.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `factor` is less than 1, infinite, or NaN.
+    #[inline]
+    pub fn set_growth_factor(&mut self, factor: f32) {
+        check_growth_factor(factor);
+        self.growth_factor = factor;
+    }
 
-impl IntoFlat<u8, String> for &str {
+    /// Returns the number of bytes of heap memory held by the `FlatVec`, including spare capacity.
+    ///
+    /// This does not include any memory owned by the `BackingTy` themselves.
     #[inline]
-    fn into_flat(self, mut store: Storage<u8>) {
-        store
-            .allocate(self.as_bytes().len())
-            .copy_from_slice(self.as_bytes());
+    #[must_use]
+    pub fn memory_usage(&self) -> usize {
+        let ends = if self.ends.is_inline() {
+            0
+        } else {
+            self.ends.capacity() * mem::size_of::<IndexTy>()
+        };
+        self.data.len() * mem::size_of::<BackingTy>() + ends
+    }
+
+    /// Returns the number of heap allocations held by the `FlatVec`, which is at most 2.
+    #[inline]
+    #[must_use]
+    pub fn heap_allocations(&self) -> usize {
+        let data = self.data.len() * mem::size_of::<BackingTy>() > 0;
+        let ends = !self.ends.is_inline() && self.ends.capacity() * mem::size_of::<IndexTy>() > 0;
+        usize::from(data) + usize::from(ends)
+    }
+
+    /// Shrinks the backing storage to exactly `data_len` and the storage for end offsets as much
+    /// as possible.
+    ///
+    /// The end offsets stay in their own allocation unless they fit inline. To store them in the
+    /// same allocation as the backing storage, use `into_compact`.
+    #[inline]
+    pub fn shrink_to_fit(&mut self) {
+        if self.data.len() > self.data_len {
+            let mut data = mem::take(&mut self.data).into_vec();
+            data.truncate(self.data_len);
+            self.data = data.into_boxed_slice();
+        }
+        self.shrink_ends_to_fit();
+    }
+
+    /// Shrinks the storage for end offsets as much as possible, without changing the backing
+    /// storage. If the end offsets fit in `INDEX_INLINE_LEN`, they are moved back inline and the
+    /// heap allocation is freed.
+    #[inline]
+    pub fn shrink_ends_to_fit(&mut self) {
+        self.ends.shrink_to_fit();
+    }
+
+    /// Shortens the `FlatVec`, keeping the first `len` elements and dropping the rest. This has
+    /// no effect if `len` is greater than or equal to the current length.
+    ///
+    /// Since all the removed elements are at the end, no backing storage is moved.
+    #[inline]
+    pub fn truncate(&mut self, len: usize) {
+        if len < self.len() {
+            self.data_len = self.start_of(len);
+            self.ends.truncate(len);
+        }
+    }
+
+    // Hands out the unused backing storage so that a new element can be flattened onto the end
+    fn storage(&mut self) -> Storage<'_, BackingTy> {
+        Storage {
+            start: self.data_len,
+            data: &mut self.data,
+            data_len: &mut self.data_len,
+            growth_factor: self.growth_factor,
+        }
+    }
+
+    /// Removes all elements, but keeps the allocated capacity so that it can be reused. Use
+    /// `reset` to also free it.
+    #[inline]
+    pub fn clear(&mut self) {
+        self.data_len = 0;
+        self.ends.clear();
+    }
+
+    /// Removes all elements and frees the backing storage. The storage for end offsets returns to
+    /// inline storage, so the `FlatVec` no longer holds any heap allocations.
+    #[inline]
+    pub fn reset(&mut self) {
+        self.data = Box::default();
+        self.data_len = 0;
+        self.ends = TinyVec::default();
+    }
+
+    /// Appends an element to the back of the collection.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the new `data_len` cannot be represented as an `IndexTy`.
+    #[inline]
+    pub fn push<Source>(&mut self, input: Source)
+    where
+        Source: IntoFlat<BackingTy, T>,
+    {
+        self.try_push(input).unwrap();
+    }
+
+    /// Appends an element to the back of the collection, or returns an error if the new
+    /// `data_len` cannot be represented as an `IndexTy`.
+    ///
+    /// If this returns an error, the `FlatVec` is left holding exactly the elements it held
+    /// before the call.
+    #[inline]
+    pub fn try_push<Source>(&mut self, input: Source) -> Result<(), CapacityError>
+    where
+        Source: IntoFlat<BackingTy, T>,
+    {
+        self.try_push_with(|storage| input.into_flat(storage))
+    }
+
+    /// Appends an element to the back of the collection if the new `data_len` can be represented
+    /// as an `IndexTy`, and returns whether it was stored.
+    ///
+    /// If the element is discarded, the `FlatVec` is left holding exactly the elements it held
+    /// before the call, as with `try_push`.
+    #[inline]
+    pub fn push_saturating<Source>(&mut self, input: Source) -> bool
+    where
+        Source: IntoFlat<BackingTy, T>,
+    {
+        self.try_push(input).is_ok()
+    }
+
+    /// Appends an element to the back of the collection, which is stored by `f` instead of an
+    /// `IntoFlat` implementation.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the new `data_len` cannot be represented as an `IndexTy`.
+    #[inline]
+    pub fn push_with<F>(&mut self, f: F)
+    where
+        F: FnOnce(Storage<BackingTy>),
+    {
+        self.try_push_with(f).unwrap();
+    }
+
+    /// Appends an element to the back of the collection, which is stored by `f` instead of an
+    /// `IntoFlat` implementation, or returns an error if the new `data_len` cannot be represented
+    /// as an `IndexTy`.
+    ///
+    /// If this returns an error, the `FlatVec` is left holding exactly the elements it held
+    /// before the call.
+    #[inline]
+    pub fn try_push_with<F>(&mut self, f: F) -> Result<(), CapacityError>
+    where
+        F: FnOnce(Storage<BackingTy>),
+    {
+        let old_data_len = self.data_len;
+        f(self.storage());
+        match IndexTy::try_from(self.data_len) {
+            Ok(end) => {
+                self.ends.push(end);
+                Ok(())
+            }
+            Err(_) => {
+                let offset = self.data_len;
+                self.data_len = old_data_len;
+                Err(CapacityError { offset })
+            }
+        }
+    }
+
+    /// Inserts an element at position `index`, shifting all elements after it to the right.
+    /// This function is `O(self.len() + self.data_len())`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `index > len`, or if the new `data_len` cannot be represented as an `IndexTy`.
+    #[inline]
+    pub fn insert<Source>(&mut self, index: usize, input: Source)
+    where
+        Source: IntoFlat<BackingTy, T>,
+    {
+        let len = self.len();
+        assert!(
+            index <= len,
+            "insertion index (is {}) should be <= len (is {})",
+            index,
+            len
+        );
+        let old_data_len = self.data_len;
+        self.push(input);
+        if index == len {
+            return;
+        }
+
+        // The new element was flattened onto the end, rotate it into place
+        let start = if index == 0 {
+            0
+        } else {
+            self.ends[index - 1].try_into().unwrap()
+        };
+        let inserted_len = self.data_len - old_data_len;
+        self.data[start..self.data_len].rotate_right(inserted_len);
+        self.ends.pop();
+        self.ends.iter_mut().skip(index).for_each(|end| {
+            let change = usize::try_from(*end).unwrap() + inserted_len;
+            *end = change.try_into().unwrap();
+        });
+        self.ends
+            .insert(index, (start + inserted_len).try_into().unwrap());
+    }
+
+    /// Removes the last element and constructs a `Dest` from its stored representation, or
+    /// returns `None` if the `FlatVec` is empty.
+    ///
+    /// Because the element is removed before this returns, `Dest` cannot borrow from the
+    /// `FlatVec`. This function is `O(1)`.
+    #[inline]
+    pub fn pop<Dest>(&mut self) -> Option<Dest>
+    where
+        Dest: for<'b> FromFlat<'b, BackingTy, T>,
+    {
+        let end = self.ends.pop()?.try_into().unwrap();
+        let start = match self.ends.last() {
+            Some(&start) => start.try_into().unwrap(),
+            None => 0,
+        };
+        self.data_len = start;
+        Some(Dest::from_flat(&self.data[start..end]))
+    }
+
+    /// Removes the `index`th element of a `FlatVec` by moving the last element into its place.
+    ///
+    /// Afterwards, the element that was last is at `index` and all other elements are where they
+    /// were. If the removed element and the last element have the same length, only the last
+    /// element's storage is moved. Otherwise, the storage of the elements between them is also
+    /// shifted to close the gap.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `index` is out of bounds.
+    #[inline]
+    pub fn swap_remove(&mut self, index: usize) {
+        let len = self.len();
+        assert!(
+            index < len,
+            "swap_remove index (is {}) should be < len (is {})",
+            index,
+            len
+        );
+        let start = if index == 0 {
+            0
+        } else {
+            self.ends[index - 1].try_into().unwrap()
+        };
+        let end: usize = self.ends[index].try_into().unwrap();
+        if index == len - 1 {
+            self.ends.pop();
+            self.data_len = start;
+            return;
+        }
+
+        let last_start: usize = self.ends[len - 2].try_into().unwrap();
+        self.ends.pop();
+        let last_end = self.data_len;
+        let removed_len = end - start;
+        let moved_len = last_end - last_start;
+        if removed_len == moved_len {
+            let (head, tail) = self.data.split_at_mut(last_start);
+            head[start..end].swap_with_slice(&mut tail[..moved_len]);
+        } else {
+            // [removed][middle][last] -> [middle][last][removed] -> [last][middle][removed]
+            self.data[start..last_end].rotate_left(removed_len);
+            self.data[start..last_end - removed_len].rotate_right(moved_len);
+            self.ends.iter_mut().skip(index + 1).for_each(|end| {
+                let change = usize::try_from(*end).unwrap() + moved_len - removed_len;
+                *end = change.try_into().unwrap();
+            });
+        }
+        self.ends[index] = (start + moved_len).try_into().unwrap();
+        self.data_len -= removed_len;
+    }
+
+    /// Removes the `index`th element like `swap_remove`, and constructs a `Dest` from its stored
+    /// representation. Returns `None` if `index` is out of bounds.
+    ///
+    /// Because the element is removed before this returns, `Dest` cannot borrow from the
+    /// `FlatVec`.
+    #[inline]
+    pub fn swap_remove_into<Dest>(&mut self, index: usize) -> Option<Dest>
+    where
+        Dest: for<'b> FromFlat<'b, BackingTy, T>,
+    {
+        let removed = Dest::from_flat(self.raw_get(index)?);
+        self.swap_remove(index);
+        Some(removed)
+    }
+
+    /// Swaps the elements at indices `a` and `b`.
+    ///
+    /// If the two elements have different lengths, the backing storage of all the elements
+    /// between them is also shifted.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `a` or `b` are out of bounds.
+    #[inline]
+    pub fn swap(&mut self, a: usize, b: usize) {
+        let len = self.len();
+        assert!(
+            a < len,
+            "index out of bounds: the len is {} but the index is {}",
+            len,
+            a
+        );
+        assert!(
+            b < len,
+            "index out of bounds: the len is {} but the index is {}",
+            len,
+            b
+        );
+        let (first, second) = if a < b { (a, b) } else { (b, a) };
+        if first == second {
+            return;
+        }
+
+        let start = self.start_of(first);
+        let end = self.start_of(second + 1);
+        let first_len = self.start_of(first + 1) - start;
+        let second_len = end - self.start_of(second);
+        if first_len == second_len {
+            let (head, tail) = self.data.split_at_mut(end - second_len);
+            head[start..start + first_len].swap_with_slice(&mut tail[..second_len]);
+            return;
+        }
+
+        // [first][middle][second] -> [middle][second][first] -> [second][middle][first]
+        self.data[start..end].rotate_left(first_len);
+        self.data[start..end - first_len].rotate_right(second_len);
+        self.ends[first] = (start + second_len).try_into().unwrap();
+        self.ends
+            .iter_mut()
+            .take(second)
+            .skip(first + 1)
+            .for_each(|end| {
+                let change = usize::try_from(*end).unwrap() + second_len - first_len;
+                *end = change.try_into().unwrap();
+            });
+    }
+
+    /// Reverses the order of the elements in place.
+    ///
+    /// This function is `O(self.len() + self.data_len())` and does not allocate.
+    #[inline]
+    pub fn reverse(&mut self) {
+        let len = self.len();
+        if len < 2 {
+            return;
+        }
+        // Reversing all the data reverses the order of the elements but also the contents of each
+        // element, so each element is reversed again afterwards
+        self.data[..self.data_len].reverse();
+        // The start of each element in the old order is its distance from the end in the new one
+        self.ends.reverse();
+        self.ends.rotate_left(1);
+        let data_len = self.data_len;
+        self.ends.iter_mut().take(len - 1).for_each(|end| {
+            let change = data_len - usize::try_from(*end).unwrap();
+            *end = change.try_into().unwrap();
+        });
+        let mut start = 0;
+        for end in self.ends.iter() {
+            let end = usize::try_from(*end).unwrap();
+            self.data[start..end].reverse();
+            start = end;
+        }
+    }
+
+    /// Rotates the elements in place such that the first `mid` elements move to the end while the
+    /// last `self.len() - mid` elements move to the front.
+    ///
+    /// This function is `O(self.len() + self.data_len())` and does not allocate.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `mid` is greater than `self.len()`.
+    #[inline]
+    pub fn rotate_left(&mut self, mid: usize) {
+        let len = self.len();
+        assert!(mid <= len, "mid {} is greater than the len {}", mid, len);
+        let shift = self.start_of(mid);
+        let data_len = self.data_len;
+        self.data[..data_len].rotate_left(shift);
+        self.ends.rotate_left(mid);
+        for (i, end) in self.ends.iter_mut().enumerate() {
+            let end_usize = usize::try_from(*end).unwrap();
+            let change = if i < len - mid {
+                end_usize - shift
+            } else {
+                end_usize + data_len - shift
+            };
+            *end = change.try_into().unwrap();
+        }
+    }
+
+    /// Rotates the elements in place such that the last `k` elements move to the front while the
+    /// first `self.len() - k` elements move to the end.
+    ///
+    /// This function is `O(self.len() + self.data_len())` and does not allocate.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `k` is greater than `self.len()`.
+    #[inline]
+    pub fn rotate_right(&mut self, k: usize) {
+        let len = self.len();
+        assert!(k <= len, "k {} is greater than the len {}", k, len);
+        self.rotate_left(len - k);
+    }
+
+    /// Returns an iterator that consumes the `FlatVec` and constructs a `Dest` from each element's
+    /// stored representation.
+    ///
+    /// Because the backing storage is freed when the iterator is dropped, `Dest` cannot borrow
+    /// from the `FlatVec`.
+    // IntoIterator can't be implemented because the caller picks the Item type
+    #[allow(clippy::should_implement_trait)]
+    #[inline]
+    pub fn into_iter<Dest>(self) -> IntoIter<T, Dest, IndexTy, BackingTy, INDEX_INLINE_LEN>
+    where
+        Dest: for<'b> FromFlat<'b, BackingTy, T>,
+    {
+        IntoIter {
+            data: self.data,
+            ends: self.ends.into_iter(),
+            start: 0,
+            marker: PhantomData,
+        }
+    }
+
+    /// Consumes the `FlatVec` and constructs a `Dest` from each element's stored representation,
+    /// collecting them into a `Vec`. The backing storage is freed before this returns.
+    #[inline]
+    #[must_use]
+    pub fn into_vec<Dest>(self) -> Vec<Dest>
+    where
+        Dest: for<'b> FromFlat<'b, BackingTy, T>,
+    {
+        self.into_iter().collect()
+    }
+
+    /// Converts the `FlatVec` into one which shares its backing storage when it is cloned, so
+    /// that cloning does not copy the elements. The result can only be read from.
+    #[inline]
+    #[must_use]
+    pub fn into_shared(
+        mut self,
+    ) -> FlatVec<T, IndexTy, BackingTy, INDEX_INLINE_LEN, Arc<[BackingTy]>> {
+        self.shrink_to_fit();
+        FlatVec {
+            data: Arc::from(self.data),
+            data_len: self.data_len,
+            ends: self.ends,
+            growth_factor: self.growth_factor,
+            marker: PhantomData,
+        }
     }
 }
 
-impl<'a> FromFlat<'a, u8, String> for &'a str {
-    #[inline]
-    fn from_flat(data: &'a [u8]) -> &'a str {
-        str::from_utf8(&data).unwrap()
+impl<'a, T: 'a, IndexTy, BackingTy, const INDEX_INLINE_LEN: usize, Data>
+    FlatVec<T, IndexTy, BackingTy, INDEX_INLINE_LEN, Data>
+where
+    IndexTy: Default,
+    IndexTy: TryFrom<usize> + Copy + Sub,
+    usize: TryFrom<IndexTy>,
+    <IndexTy as TryFrom<usize>>::Error: fmt::Debug,
+    <usize as TryFrom<IndexTy>>::Error: fmt::Debug,
+    Data: AsRef<[BackingTy]>,
+{
+    /// Creates a `FlatVec` which uses all of `data` as the backing storage of its elements, where
+    /// `ends` is the end offset of each element. Unlike `from_backing`, `data` is not copied or
+    /// converted, so it can be borrowed, or owned by another type such as a memory map.
+    ///
+    /// Returns an error if `ends` is not sorted or does not end at `data.as_ref().len()`.
+    #[inline]
+    pub fn from_data(data: Data, ends: Vec<IndexTy>) -> Result<Self, LayoutError> {
+        let () = Self::INLINE_LEN_IS_NONZERO;
+        let data_len = data.as_ref().len();
+        validate_layout(&ends, data_len, data_len)?;
+        Ok(Self {
+            data,
+            data_len,
+            ends: ends.into_iter().collect(),
+            growth_factor: DEFAULT_GROWTH_FACTOR,
+            marker: PhantomData,
+        })
+    }
+
+    /// Returns `true` if `self` and `other` use the same backing storage, such as after cloning a
+    /// `FlatVec` which was created by `into_shared`, and `false` if it was copied.
+    ///
+    /// Backing storage which has no capacity is not allocated, so it may compare equal.
+    #[inline]
+    #[must_use]
+    pub fn ptr_eq_backing<OtherData>(
+        &self,
+        other: &FlatVec<T, IndexTy, BackingTy, INDEX_INLINE_LEN, OtherData>,
+    ) -> bool
+    where
+        OtherData: AsRef<[BackingTy]>,
+    {
+        ptr::eq(self.data.as_ref(), other.data.as_ref())
+    }
+
+    /// Returns the number of `T` in a `FlatVec<T>`.
+    #[inline]
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.ends.len()
+    }
+
+    /// Returns the number of `BackingTy` used to store the elements of a `FlatVec`. This does not
+    /// necessarily correlate with storage used to store the indices.
+    #[inline]
+    #[must_use]
+    pub fn data_len(&self) -> usize {
+        self.data_len
+    }
+
+    /// Returns the backing storage of all the elements, in order.
+    #[inline]
+    #[must_use]
+    pub fn data_as_slice(&self) -> &[BackingTy] {
+        &self.data.as_ref()[..self.data_len]
+    }
+
+    /// Returns the end offset of each element in the backing storage, in order. The `index`th
+    /// element occupies `ends[index - 1]..ends[index]`, or `0..ends[0]` for the first element.
+    #[inline]
+    #[must_use]
+    pub fn ends(&self) -> &[IndexTy] {
+        &self.ends
+    }
+
+    /// Returns the largest `data_len` that can be represented by `IndexTy`. Pushing an element
+    /// which would make `data_len` larger than this fails.
+    #[inline]
+    #[must_use]
+    pub fn max_data_len(&self) -> usize {
+        // The largest value of an unsigned integer as wide as IndexTy, saturated to usize, or half
+        // of that if IndexTy is signed
+        let unsigned_max = match mem::size_of::<IndexTy>().checked_mul(8) {
+            Some(bits) if bits < 8 * mem::size_of::<usize>() => (1 << bits) - 1,
+            _ => usize::MAX,
+        };
+        if IndexTy::try_from(unsigned_max).is_ok() {
+            unsigned_max
+        } else {
+            unsigned_max >> 1
+        }
+    }
+
+    /// Returns how many more `BackingTy` can be stored before `data_len` can no longer be
+    /// represented by `IndexTy`.
+    #[inline]
+    #[must_use]
+    pub fn remaining_capacity(&self) -> usize {
+        self.max_data_len() - self.data_len
+    }
+
+    /// Returns the number of elements the `FlatVec` can store end offsets for without
+    /// reallocating.
+    #[inline]
+    #[must_use]
+    pub fn ends_capacity(&self) -> usize {
+        self.ends.capacity()
+    }
+
+    /// Returns true if the end offsets are stored inline, which is the case until more than
+    /// `INDEX_INLINE_LEN` elements have been stored.
+    #[inline]
+    #[must_use]
+    pub fn is_ends_inline(&self) -> bool {
+        self.ends.is_inline()
+    }
+
+    /// Returns the number of `BackingTy` used to store the `index`th element, or `None` if
+    /// `index` is out of bounds.
+    #[inline]
+    #[must_use]
+    pub fn element_len(&self, index: usize) -> Option<usize> {
+        self.byte_range(index).map(|range| range.len())
+    }
+
+    // Returns the offset in data where the index'th element starts, which is also the end of the
+    // previous element
+    #[inline]
+    fn start_of(&self, index: usize) -> usize {
+        self.offsets_iter().start_of(index)
+    }
+
+    /// Returns true if the len is 0.
+    #[inline]
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.ends.len() == 0
+    }
+
+    /// Construct a `Dest` from the `index`th element's stored representation.
+    #[inline]
+    #[must_use]
+    pub fn get<Dest>(&'a self, index: usize) -> Option<Dest>
+    where
+        Dest: FromFlat<'a, BackingTy, T> + 'a,
+    {
+        self.raw_get(index).map(Dest::from_flat)
+    }
+
+    /// Construct a `Dest` from each of the elements at `indices`, or return `None` if any index is
+    /// out of bounds.
+    ///
+    /// The indices do not need to be distinct or sorted.
+    #[inline]
+    #[must_use]
+    pub fn get_many<Dest, const K: usize>(&'a self, indices: [usize; K]) -> Option<[Dest; K]>
+    where
+        Dest: FromFlat<'a, BackingTy, T> + 'a,
+    {
+        if indices.iter().any(|&index| index >= self.len()) {
+            return None;
+        }
+        Some(indices.map(|index| Dest::from_flat(self.raw_get(index).unwrap())))
+    }
+
+    /// Construct a `Dest` which does not borrow from the `FlatVec` from the `index`th element's
+    /// stored representation.
+    ///
+    /// Unlike `get`, the returned value does not keep `self` borrowed, so the `FlatVec` can be
+    /// modified while it is alive.
+    #[inline]
+    #[must_use]
+    pub fn get_owned<Dest>(&self, index: usize) -> Option<Dest>
+    where
+        Dest: for<'b> FromFlat<'b, BackingTy, T>,
+    {
+        self.raw_get(index).map(Dest::from_flat)
+    }
+
+    /// Overwrites `out` with the `index`th element, reusing the allocations of `out`.
+    ///
+    /// Returns `false` and leaves `out` unchanged if `index` is out of bounds.
+    #[inline]
+    pub fn get_into<Dest>(&self, index: usize, out: &mut Dest) -> bool
+    where
+        Dest: FromFlatInto<BackingTy, T>,
+    {
+        match self.raw_get(index) {
+            Some(data) => {
+                Dest::from_flat_into(data, out);
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Try to construct a `Dest` from the `index`th element's stored representation.
+    ///
+    /// Returns `None` if `index` is out of bounds, and `Some(Err(_))` if the stored representation
+    /// was rejected by `Dest`.
+    #[inline]
+    #[must_use]
+    pub fn try_get<Dest>(&'a self, index: usize) -> Option<Result<Dest, Dest::Error>>
+    where
+        Dest: TryFromFlat<'a, BackingTy, T> + 'a,
+    {
+        self.raw_get(index).map(Dest::try_from_flat)
+    }
+
+    /// Construct a `Dest` from the `index`th element's stored representation, or return an
+    /// `IndexError` which reports `index` and the length if it is out of bounds.
+    #[inline]
+    pub fn try_get_checked<Dest>(&'a self, index: usize) -> Result<Dest, IndexError>
+    where
+        Dest: FromFlat<'a, BackingTy, T> + 'a,
+    {
+        self.get(index).ok_or(IndexError {
+            index,
+            len: self.len(),
+        })
+    }
+
+    /// Returns the stored representation of the `index`th element.
+    #[inline]
+    #[must_use]
+    pub fn raw_get(&self, index: usize) -> Option<&[BackingTy]> {
+        self.byte_range(index)
+            .map(|range| &self.data.as_ref()[range])
+    }
+
+    /// Returns the stored representation of the `index`th element, or `Ok(None)` if `index` is
+    /// out of bounds.
+    ///
+    /// Unlike `raw_get`, this checks the end offsets of the element as it reads them, and returns
+    /// a `LayoutError` instead of panicking if they cannot be converted to `usize` or do not
+    /// describe a range of the backing storage.
+    #[inline]
+    pub fn try_raw_get(&self, index: usize) -> Result<Option<&[BackingTy]>, LayoutError> {
+        if index >= self.len() {
+            return Ok(None);
+        }
+        let start = match index.checked_sub(1) {
+            Some(previous) => checked_end(&self.ends, previous)?,
+            None => 0,
+        };
+        let end = checked_end(&self.ends, index)?;
+        checked_slice(self.data.as_ref(), start..end, index).map(Some)
+    }
+
+    /// Returns the range of offsets in the backing storage where the `index`th element is
+    /// stored, or `None` if `index` is out of bounds.
+    #[inline]
+    #[must_use]
+    pub fn byte_range(&self, index: usize) -> Option<Range<usize>> {
+        if index >= self.ends.len() {
+            None
+        } else {
+            Some(self.start_of(index)..self.start_of(index + 1))
+        }
+    }
+
+    /// Construct a `Dest` from the first element's stored representation, or return `None` if
+    /// the `FlatVec` is empty.
+    #[inline]
+    #[must_use]
+    pub fn first<Dest>(&'a self) -> Option<Dest>
+    where
+        Dest: FromFlat<'a, BackingTy, T> + 'a,
+    {
+        self.get(0)
+    }
+
+    /// Construct a `Dest` from the last element's stored representation, or return `None` if
+    /// the `FlatVec` is empty.
+    #[inline]
+    #[must_use]
+    pub fn last<Dest>(&'a self) -> Option<Dest>
+    where
+        Dest: FromFlat<'a, BackingTy, T> + 'a,
+    {
+        self.get(self.len().checked_sub(1)?)
+    }
+
+    /// Binary searches this sorted `FlatVec` with a comparator function over each element's
+    /// stored representation.
+    ///
+    /// The comparator should return whether its argument is `Less`, `Equal`, or `Greater` than the
+    /// desired target. If an element is found this returns `Ok` with its index, if there are
+    /// multiple matches any one of them may be returned. Otherwise this returns `Err` with the
+    /// index where a matching element could be inserted while maintaining sorted order.
+    #[inline]
+    pub fn binary_search_by<F>(&self, mut f: F) -> Result<usize, usize>
+    where
+        F: FnMut(&[BackingTy]) -> Ordering,
+    {
+        binary_search_indices(self.len(), |index| f(self.raw_get(index).unwrap()))
+    }
+
+    /// Binary searches this `FlatVec`, which must be sorted by the key extracted from each
+    /// element by `f`, for `key`. See `binary_search_by` for the meaning of the return value.
+    #[inline]
+    pub fn binary_search_by_key<Dest, Key, F>(&'a self, key: &Key, mut f: F) -> Result<usize, usize>
+    where
+        Dest: FromFlat<'a, BackingTy, T> + 'a,
+        Key: Ord,
+        F: FnMut(Dest) -> Key,
+    {
+        binary_search_indices(self.len(), |index| f(self.get(index).unwrap()).cmp(key))
+    }
+
+    /// Returns an iterator that constructs a `Dest` from each element's stored representation.
+    #[inline]
+    pub fn iter<Dest>(&'a self) -> Iter<'a, T, Dest, IndexTy, BackingTy>
+    where
+        Dest: FromFlat<'a, BackingTy, T> + 'a,
+    {
+        Iter {
+            raw: self.raw_iter(),
+            marker: PhantomData,
+        }
+    }
+
+    /// Returns an iterator that constructs a `Dest` from each element's stored representation.
+    ///
+    /// Unlike `iter`, this checks each end offset as it reads it, and yields a `LayoutError`
+    /// instead of panicking if one cannot be converted to `usize` or does not describe a range of
+    /// the backing storage. The iterator ends after the first error.
+    #[inline]
+    pub fn try_iter<Dest>(&'a self) -> TryIter<'a, T, Dest, IndexTy, BackingTy>
+    where
+        Dest: FromFlat<'a, BackingTy, T> + 'a,
+    {
+        TryIter {
+            data: self.data.as_ref(),
+            ends: &self.ends,
+            index: 0,
+            start: 0,
+            marker: PhantomData,
+        }
+    }
+
+    /// Constructs a `Dest` from each element's stored representation, and collects them into a
+    /// `Vec`.
+    #[inline]
+    #[must_use]
+    pub fn to_vec<Dest>(&'a self) -> Vec<Dest>
+    where
+        Dest: FromFlat<'a, BackingTy, T> + 'a,
+    {
+        self.iter().collect()
+    }
+
+    /// Returns a wrapper whose `Debug` implementation formats the `FlatVec` as a list of the
+    /// `Dest` constructed from each element's stored representation, instead of the backing
+    /// storage and end offsets.
+    #[inline]
+    #[must_use]
+    pub fn debug_elements<Dest>(&'a self) -> DebugElements<'a, T, Dest, IndexTy, BackingTy>
+    where
+        Dest: FromFlat<'a, BackingTy, T> + fmt::Debug + 'a,
+    {
+        DebugElements {
+            data: self.data.as_ref(),
+            offsets: self.offsets_iter(),
+            marker: PhantomData,
+        }
+    }
+
+    /// Returns an iterator that constructs a `Dest` from the stored representation of each element
+    /// in `range`, without visiting the elements outside it.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the range is out of bounds, like slice indexing.
+    #[inline]
+    pub fn iter_range<Dest, R>(&'a self, range: R) -> Iter<'a, T, Dest, IndexTy, BackingTy>
+    where
+        Dest: FromFlat<'a, BackingTy, T> + 'a,
+        R: RangeBounds<usize>,
+    {
+        let range = resolve_range(range, self.len());
+        Iter {
+            raw: RawIter {
+                data: self.data.as_ref(),
+                offsets: Offsets {
+                    start: self.start_of(range.start),
+                    ends: &self.ends[range],
+                },
+            },
+            marker: PhantomData,
+        }
+    }
+
+    /// Returns an iterator over the stored representation of each element.
+    #[inline]
+    pub fn raw_iter(&self) -> RawIter<'_, IndexTy, BackingTy> {
+        RawIter {
+            data: self.data.as_ref(),
+            offsets: self.offsets_iter(),
+        }
+    }
+
+    /// Returns an iterator over the index and stored representation of each element.
+    #[inline]
+    pub fn enumerate_raw(&self) -> iter::Enumerate<RawIter<'_, IndexTy, BackingTy>> {
+        self.raw_iter().enumerate()
+    }
+
+    /// Returns an iterator over each element's stored representation together with the `Dest`
+    /// constructed from it.
+    #[inline]
+    pub fn iter_with_raw<Dest>(
+        &'a self,
+    ) -> impl DoubleEndedIterator<Item = (&'a [BackingTy], Dest)> + ExactSizeIterator + 'a
+    where
+        Dest: FromFlat<'a, BackingTy, T> + 'a,
+    {
+        self.raw_iter().map(|data| (data, Dest::from_flat(data)))
+    }
+
+    /// Returns an iterator over the range of the backing storage occupied by each element.
+    #[inline]
+    pub fn offsets_iter(&self) -> Offsets<'_, IndexTy> {
+        Offsets {
+            ends: &self.ends,
+            start: 0,
+        }
+    }
+
+    /// Returns an iterator over the length of each element's stored representation.
+    #[inline]
+    pub fn lengths(
+        &self,
+    ) -> impl DoubleEndedIterator<Item = usize> + ExactSizeIterator + Clone + '_ {
+        self.offsets_iter().map(|range| range.len())
+    }
+
+    /// Returns an iterator over groups of `n` consecutive elements, whose backing storage is
+    /// contiguous. The last group has fewer than `n` elements if `n` does not divide
+    /// `self.len()`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `n` is 0.
+    #[inline]
+    pub fn element_chunks(&self, n: usize) -> ElementChunks<'_, IndexTy, BackingTy> {
+        assert!(n != 0, "chunk size must be non-zero");
+        ElementChunks {
+            data: self.data.as_ref(),
+            offsets: self.offsets_iter(),
+            n,
+        }
+    }
+
+    /// Returns the index of the first element whose stored representation satisfies `f`, or
+    /// `None` if there is no such element.
+    #[inline]
+    pub fn position<F>(&self, f: F) -> Option<usize>
+    where
+        F: FnMut(&[BackingTy]) -> bool,
+    {
+        self.raw_iter().position(f)
+    }
+
+    /// Returns `true` if the stored representation of any element is equal to `needle`.
+    #[inline]
+    #[must_use]
+    pub fn contains_slice(&self, needle: &[BackingTy]) -> bool
+    where
+        BackingTy: PartialEq,
+    {
+        self.raw_iter().any(|element| element == needle)
+    }
+
+    /// Returns the number of elements whose stored representation satisfies `f`.
+    #[inline]
+    #[must_use]
+    pub fn count_by<F>(&self, mut f: F) -> usize
+    where
+        F: FnMut(&[BackingTy]) -> bool,
+    {
+        self.raw_iter().filter(|element| f(element)).count()
+    }
+
+    /// Returns the total length of the stored representations of all the elements, which is
+    /// always equal to `data_len`.
+    #[inline]
+    #[must_use]
+    pub fn total_element_bytes(&self) -> usize {
+        self.data_len
+    }
+
+    /// Folds every element's stored representation into an accumulator, in order, without
+    /// constructing any elements.
+    #[inline]
+    pub fn fold_raw<A, F>(&self, init: A, f: F) -> A
+    where
+        F: FnMut(A, &[BackingTy]) -> A,
+    {
+        self.raw_iter().fold(init, f)
+    }
+}
+
+impl<T, IndexTy, BackingTy, const INDEX_INLINE_LEN: usize>
+    FlatVec<T, IndexTy, BackingTy, INDEX_INLINE_LEN>
+where
+    IndexTy: Default,
+    IndexTy: TryFrom<usize> + Copy + Sub,
+    usize: TryFrom<IndexTy>,
+    <IndexTy as TryFrom<usize>>::Error: fmt::Debug,
+    <usize as TryFrom<IndexTy>>::Error: fmt::Debug,
+    BackingTy: Default,
+{
+    /// Create a new `FlatVec` that can hold `num_elements` elements which are stored in a total
+    /// of `num_backing` `BackingTy` without reallocating.
+    ///
+    /// Note that `num_backing` is a count of `BackingTy`, not of bytes.
+    #[inline]
+    #[must_use]
+    pub fn with_capacity(num_elements: usize, num_backing: usize) -> Self {
+        let () = Self::INLINE_LEN_IS_NONZERO;
+        let mut ends = TinyVec::default();
+        ends.reserve(num_elements);
+        Self {
+            data: iter::repeat_with(BackingTy::default)
+                .take(num_backing)
+                .collect(),
+            data_len: 0,
+            ends,
+            growth_factor: DEFAULT_GROWTH_FACTOR,
+            marker: PhantomData,
+        }
+    }
+
+    /// Reserves capacity for at least `additional` more `BackingTy` to be stored without
+    /// reallocating.
+    #[inline]
+    pub fn reserve_data(&mut self, additional: usize) {
+        self.storage().reserve(additional);
+    }
+
+    /// Tries to reserve capacity for at least `additional` more `BackingTy` to be stored without
+    /// reallocating, and returns an error instead of aborting if the allocation fails.
+    ///
+    /// If this returns an error, the `FlatVec` is unchanged.
+    #[inline]
+    pub fn try_reserve_data(&mut self, additional: usize) -> Result<(), TryReserveError> {
+        self.storage().try_reserve(additional)
+    }
+
+    /// Tries to reserve capacity for at least `additional` more `BackingTy` and the end offsets of
+    /// `additional` more elements, and returns an error instead of aborting if an allocation
+    /// fails.
+    ///
+    /// If this returns an error, the elements of the `FlatVec` are unchanged, though the capacity
+    /// of the backing storage may have grown.
+    #[inline]
+    pub fn try_reserve(&mut self, additional: usize) -> Result<(), TryReserveError> {
+        self.storage().try_reserve(additional)?;
+        self.ends.try_reserve(additional)
+    }
+
+    /// Returns a `Builder` for configuring the capacity and growth factor of a new `FlatVec`.
+    #[inline]
+    #[must_use]
+    pub fn builder() -> Builder<T, IndexTy, BackingTy, INDEX_INLINE_LEN> {
+        Builder {
+            num_elements: 0,
+            num_backing: 0,
+            growth_factor: DEFAULT_GROWTH_FACTOR,
+            marker: PhantomData,
+        }
+    }
+}
+
+impl<T, IndexTy, BackingTy, const INDEX_INLINE_LEN: usize>
+    FlatVec<T, IndexTy, BackingTy, INDEX_INLINE_LEN>
+where
+    IndexTy: Default,
+    IndexTy: TryFrom<usize> + Copy + Sub,
+    usize: TryFrom<IndexTy>,
+    <IndexTy as TryFrom<usize>>::Error: fmt::Debug,
+    <usize as TryFrom<IndexTy>>::Error: fmt::Debug,
+    BackingTy: Copy,
+{
+    /// Removes the `index`th element of a `FlatVec`.
+    /// This function is `O(self.len() + self.data_len())`.
+    #[inline]
+    pub fn remove(&mut self, index: usize) {
+        let end: usize = self.ends[index].try_into().unwrap();
+        let start = if index == 0 {
+            0
+        } else {
+            self.ends[index - 1].try_into().unwrap()
+        };
+        self.data.copy_within(end..self.data_len, start);
+        self.ends.remove(index);
+        let removed_len = end - start;
+        self.data_len -= removed_len;
+        self.ends.iter_mut().skip(index).for_each(|end| {
+            let change = usize::try_from(*end).unwrap() - removed_len;
+            *end = change.try_into().unwrap();
+        });
+    }
+
+    /// Removes the elements in `range` from the `FlatVec`, returning an iterator that constructs a
+    /// `Dest` from each removed element's stored representation.
+    ///
+    /// The elements are removed when the iterator is dropped, even if it was not fully consumed,
+    /// and the backing storage after `range` is shifted only once. Because the elements are
+    /// removed, `Dest` cannot borrow from the `FlatVec`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the start of `range` is greater than its end, or if its end is greater than
+    /// `self.len()`.
+    #[inline]
+    pub fn drain<Dest, R>(
+        &mut self,
+        range: R,
+    ) -> Drain<'_, T, Dest, IndexTy, BackingTy, INDEX_INLINE_LEN>
+    where
+        Dest: for<'b> FromFlat<'b, BackingTy, T>,
+        R: RangeBounds<usize>,
+    {
+        let range = resolve_range(range, self.len());
+        Drain {
+            remaining: range.clone(),
+            range,
+            vec: self,
+            marker: PhantomData,
+        }
+    }
+
+    /// Removes the elements in `range` from the `FlatVec`.
+    ///
+    /// The backing storage after `range` is shifted only once, so this is
+    /// `O(self.len() + self.data_len())` no matter how many elements are removed.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the start of `range` is greater than its end, or if its end is greater than
+    /// `self.len()`.
+    #[inline]
+    pub fn remove_range<R>(&mut self, range: R)
+    where
+        R: RangeBounds<usize>,
+    {
+        let range = resolve_range(range, self.len());
+        self.remove_range_unchecked(range);
+    }
+
+    // Removes the elements in range, which must already be checked to be in bounds
+    fn remove_range_unchecked(&mut self, range: Range<usize>) {
+        if range.is_empty() {
+            return;
+        }
+        let start = self.start_of(range.start);
+        let end = self.start_of(range.end);
+        let removed_len = end - start;
+        self.data.copy_within(end..self.data_len, start);
+        self.data_len -= removed_len;
+        self.ends.iter_mut().skip(range.end).for_each(|end| {
+            let change = usize::try_from(*end).unwrap() - removed_len;
+            *end = change.try_into().unwrap();
+        });
+        self.ends.drain(range);
+    }
+
+    /// Splits the `FlatVec` into two at the given index.
+    ///
+    /// Returns a newly allocated `FlatVec` containing the elements `[at, len)`, and `self` is left
+    /// containing the elements `[0, at)`. The capacity of `self` is unchanged.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `at > len`.
+    #[inline]
+    #[must_use = "use `.truncate()` if you don't need the other half"]
+    pub fn split_off(&mut self, at: usize) -> Self {
+        let len = self.len();
+        assert!(
+            at <= len,
+            "`at` split index (is {}) should be <= len (is {})",
+            at,
+            len
+        );
+        let boundary = self.start_of(at);
+        let other = Self {
+            data: self.data[boundary..self.data_len].into(),
+            data_len: self.data_len - boundary,
+            ends: self.ends[at..]
+                .iter()
+                .map(|&end| {
+                    (usize::try_from(end).unwrap() - boundary)
+                        .try_into()
+                        .unwrap()
+                })
+                .collect(),
+            growth_factor: self.growth_factor,
+            marker: PhantomData,
+        };
+        self.truncate(at);
+        other
+    }
+
+    /// Moves all the elements of `other` onto the end of `self`, leaving `other` empty.
+    ///
+    /// This copies the backing storage of `other` directly and does not go through `IntoFlat`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the combined `data_len` cannot be represented as an `IndexTy`. In that case,
+    /// neither `FlatVec` is modified.
+    #[inline]
+    pub fn append(&mut self, other: &mut Self)
+    where
+        BackingTy: Default,
+    {
+        let offset = self.data_len;
+        let data_len = offset + other.data_len;
+        if IndexTy::try_from(data_len).is_err() {
+            panic!("{}", CapacityError { offset: data_len });
+        }
+        self.reserve_data(other.data_len);
+        self.data[offset..data_len].copy_from_slice(&other.data[..other.data_len]);
+        self.data_len = data_len;
+        self.ends.extend(
+            other
+                .ends
+                .iter()
+                .map(|&end| (usize::try_from(end).unwrap() + offset).try_into().unwrap()),
+        );
+        other.clear();
+    }
+
+    /// Concatenates several `FlatVec`s into one, in order, such as those built independently on
+    /// different threads.
+    ///
+    /// The combined backing storage is allocated once up front, then each `FlatVec` is moved in
+    /// as if by `append`. The result has the growth factor of the first `FlatVec`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the combined `data_len` cannot be represented as an `IndexTy`.
+    #[inline]
+    #[must_use]
+    pub fn merge(vecs: Vec<Self>) -> Self
+    where
+        BackingTy: Default,
+    {
+        let num_elements = vecs.iter().map(Self::len).sum();
+        let num_backing = vecs.iter().map(Self::data_len).sum();
+        let mut merged = Self::with_capacity(num_elements, num_backing);
+        if let Some(first) = vecs.first() {
+            merged.growth_factor = first.growth_factor;
+        }
+        for mut vec in vecs {
+            merged.append(&mut vec);
+        }
+        merged
+    }
+
+    /// Sorts the elements with a comparator function over their stored representations. This
+    /// sort is unstable, it may reorder equal elements.
+    ///
+    /// This function performs `O(self.len() * log(self.len()))` comparisons to sort the elements'
+    /// indices, followed by a single `O(self.len() + self.data_len())` pass to rearrange them.
+    #[inline]
+    pub fn sort_unstable_by<F>(&mut self, mut f: F)
+    where
+        F: FnMut(&[BackingTy], &[BackingTy]) -> Ordering,
+    {
+        let mut order = (0..self.len()).collect::<Vec<_>>();
+        order.sort_unstable_by(|&a, &b| f(self.raw_get(a).unwrap(), self.raw_get(b).unwrap()));
+        self.reorder(&order);
+    }
+
+    // Rearranges the elements so that the element at order[i] is moved to i
+    fn reorder(&mut self, order: &[usize]) {
+        let ranges = order
+            .iter()
+            .map(|&index| self.start_of(index)..self.start_of(index + 1))
+            .collect::<Vec<_>>();
+        let old_data = self.data[..self.data_len].to_vec();
+        let mut written = 0;
+        for (end, range) in self.ends.iter_mut().zip(ranges) {
+            let len = range.len();
+            self.data[written..written + len].copy_from_slice(&old_data[range]);
+            written += len;
+            *end = written.try_into().unwrap();
+        }
+    }
+
+    /// Removes consecutive elements with equal stored representations, keeping the first of each
+    /// run. If the `FlatVec` is sorted, this removes all duplicates.
+    /// This function is `O(self.len() + self.data_len())`.
+    #[inline]
+    pub fn dedup(&mut self)
+    where
+        BackingTy: PartialEq,
+    {
+        if self.len() < 2 {
+            return;
+        }
+        let mut kept = 1;
+        let mut kept_start = 0;
+        let mut written = self.start_of(1);
+        let mut start = written;
+        for index in 1..self.ends.len() {
+            let end: usize = self.ends[index].try_into().unwrap();
+            if self.data[start..end] != self.data[kept_start..written] {
+                if written != start {
+                    self.data.copy_within(start..end, written);
+                }
+                kept_start = written;
+                written += end - start;
+                self.ends[kept] = written.try_into().unwrap();
+                kept += 1;
+            }
+            start = end;
+        }
+        self.ends.truncate(kept);
+        self.data_len = written;
+    }
+
+    /// Replaces the `index`th element with `input`.
+    ///
+    /// If the new element has the same length as the one it replaces, it is written in place.
+    /// Otherwise, the storage of all the elements after `index` is shifted to fit it.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `index` is out of bounds, or if the new `data_len` cannot be represented as an
+    /// `IndexTy`.
+    #[inline]
+    pub fn set<Source>(&mut self, index: usize, input: Source)
+    where
+        Source: IntoFlat<BackingTy, T>,
+    {
+        let len = self.len();
+        assert!(
+            index < len,
+            "index out of bounds: the len is {} but the index is {}",
+            len,
+            index
+        );
+        let start = self.start_of(index);
+        let old_len = self.start_of(index + 1) - start;
+
+        // Flatten the new element onto the end, then move it into place
+        let old_data_len = self.data_len;
+        input.into_flat(self.storage());
+        let new_len = self.data_len - old_data_len;
+        let data_len = old_data_len - old_len + new_len;
+        if IndexTy::try_from(data_len).is_err() {
+            self.data_len = old_data_len;
+            panic!("{}", CapacityError { offset: data_len });
+        }
+
+        if new_len == old_len {
+            self.data.copy_within(old_data_len..self.data_len, start);
+        } else {
+            // [old][rest][new] -> [rest][new][old] -> [new][rest][old]
+            self.data[start..self.data_len].rotate_left(old_len);
+            self.data[start..self.data_len - old_len].rotate_right(new_len);
+            self.ends.iter_mut().skip(index).for_each(|end| {
+                let change = usize::try_from(*end).unwrap() + new_len - old_len;
+                *end = change.try_into().unwrap();
+            });
+        }
+        self.data_len = data_len;
+    }
+
+    /// Appends the stored representation of every element to `out`, end-to-end.
+    #[inline]
+    pub fn concat_into(&self, out: &mut Vec<BackingTy>) {
+        out.extend_from_slice(&self.data[..self.data_len]);
+    }
+
+    /// Returns the stored representation of every element, end-to-end.
+    #[inline]
+    #[must_use]
+    pub fn concat(&self) -> Vec<BackingTy> {
+        self.data[..self.data_len].to_vec()
+    }
+
+    /// Returns the stored representation of every element, with `sep` inserted between each
+    /// adjacent pair of elements.
+    #[inline]
+    #[must_use]
+    pub fn join(&self, sep: &[BackingTy]) -> Vec<BackingTy> {
+        let mut out = Vec::with_capacity(self.data_len + sep.len() * self.len().saturating_sub(1));
+        for (i, element) in self.raw_iter().enumerate() {
+            if i > 0 {
+                out.extend_from_slice(sep);
+            }
+            out.extend_from_slice(element);
+        }
+        out
+    }
+
+    /// Returns the index of the first element whose stored representation is equal to `data`,
+    /// or pushes a copy of `data` as a new element and returns its index.
+    ///
+    /// This finds existing elements with a linear search, so it is
+    /// `O(self.len() + self.data_len())`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if a new element is pushed and the new `data_len` cannot be represented as an
+    /// `IndexTy`.
+    #[inline]
+    pub fn find_or_push(&mut self, data: &[BackingTy]) -> usize
+    where
+        BackingTy: Default + PartialEq,
+    {
+        match self.raw_iter().position(|element| element == data) {
+            Some(index) => index,
+            None => {
+                self.push_with(|mut storage| storage.extend_from_slice(data));
+                self.len() - 1
+            }
+        }
+    }
+
+    /// Retains only the elements whose stored representation satisfies the predicate `f`,
+    /// preserving the order of the retained elements.
+    /// This function is `O(self.len() + self.data_len())`.
+    #[inline]
+    pub fn retain<F>(&mut self, mut f: F)
+    where
+        F: FnMut(&[BackingTy]) -> bool,
+    {
+        self.retain_mut(|element| f(element));
+    }
+
+    /// Retains only the elements whose stored representation satisfies the predicate `f`,
+    /// preserving the order of the retained elements. The predicate may also modify the stored
+    /// representation of each element in place.
+    ///
+    /// The predicate is given a slice, so it cannot change the length of an element; an element
+    /// which needs a different length must be replaced with `set` instead.
+    /// This function is `O(self.len() + self.data_len())`.
+    #[inline]
+    pub fn retain_mut<F>(&mut self, mut f: F)
+    where
+        F: FnMut(&mut [BackingTy]) -> bool,
+    {
+        let mut start = 0;
+        let mut written = 0;
+        let mut kept = 0;
+        for index in 0..self.ends.len() {
+            let end: usize = self.ends[index].try_into().unwrap();
+            if f(&mut self.data[start..end]) {
+                if written != start {
+                    self.data.copy_within(start..end, written);
+                }
+                written += end - start;
+                self.ends[kept] = written.try_into().unwrap();
+                kept += 1;
+            }
+            start = end;
+        }
+        self.ends.truncate(kept);
+        self.data_len = written;
+    }
+
+    /// Removes the elements at each of `indices`, preserving the order of the other elements.
+    ///
+    /// The elements which are kept are each shifted at most once, so this is
+    /// `O(self.len() + self.data_len())` no matter how many elements are removed.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `indices` is not sorted in strictly increasing order, or if any index is out of
+    /// bounds. In that case, no elements are removed.
+    #[inline]
+    pub fn remove_indices(&mut self, indices: &[usize]) {
+        for pair in indices.windows(2) {
+            assert!(
+                pair[0] < pair[1],
+                "remove_indices indices should be strictly increasing (found {} before {})",
+                pair[0],
+                pair[1]
+            );
+        }
+        if let Some(&last) = indices.last() {
+            let len = self.len();
+            assert!(
+                last < len,
+                "remove_indices index (is {}) should be < len (is {})",
+                last,
+                len
+            );
+        }
+
+        let mut removed = indices.iter().copied().peekable();
+        let mut index = 0;
+        self.retain_mut(|_| {
+            let keep = removed.next_if_eq(&index).is_none();
+            index += 1;
+            keep
+        });
+    }
+}
+
+/// Configures the capacity and growth factor of a new `FlatVec`, created by `FlatVec::builder`.
+#[derive(Clone, Debug)]
+pub struct Builder<T, IndexTy, BackingTy, const INDEX_INLINE_LEN: usize> {
+    num_elements: usize,
+    num_backing: usize,
+    growth_factor: f32,
+    marker: PhantomData<(T, IndexTy, BackingTy)>,
+}
+
+impl<T, IndexTy, BackingTy, const INDEX_INLINE_LEN: usize>
+    Builder<T, IndexTy, BackingTy, INDEX_INLINE_LEN>
+where
+    IndexTy: Default,
+    IndexTy: TryFrom<usize> + Copy + Sub,
+    usize: TryFrom<IndexTy>,
+    <IndexTy as TryFrom<usize>>::Error: fmt::Debug,
+    <usize as TryFrom<IndexTy>>::Error: fmt::Debug,
+    BackingTy: Default,
+{
+    /// Sets the number of `BackingTy` the `FlatVec` can store before reallocating.
+    #[inline]
+    #[must_use]
+    pub fn data_capacity(mut self, num_backing: usize) -> Self {
+        self.num_backing = num_backing;
+        self
+    }
+
+    /// Sets the number of elements the `FlatVec` can store before reallocating the storage for
+    /// their end offsets.
+    #[inline]
+    #[must_use]
+    pub fn element_capacity(mut self, num_elements: usize) -> Self {
+        self.num_elements = num_elements;
+        self
+    }
+
+    /// Sets the factor by which the backing storage grows when it needs to reallocate, see
+    /// `FlatVec::set_growth_factor`.
+    ///
+    /// However small the factor, every reallocation leaves at least one element of headroom, as
+    /// with `FlatVec::set_growth_factor`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `factor` is less than 1, infinite, or NaN.
+    #[inline]
+    #[must_use]
+    pub fn growth_factor(mut self, factor: f32) -> Self {
+        check_growth_factor(factor);
+        self.growth_factor = factor;
+        self
+    }
+
+    /// Creates the configured `FlatVec`.
+    #[inline]
+    #[must_use]
+    pub fn build(self) -> FlatVec<T, IndexTy, BackingTy, INDEX_INLINE_LEN> {
+        let mut vec = FlatVec::with_capacity(self.num_elements, self.num_backing);
+        vec.growth_factor = self.growth_factor;
+        vec
+    }
+}
+
+/// An iterator that moves elements out of a `FlatVec`, created by `FlatVec::into_iter`.
+pub struct IntoIter<T, Dest, IndexTy: Default, BackingTy, const INDEX_INLINE_LEN: usize> {
+    data: Box<[BackingTy]>,
+    ends: tinyvec::TinyVecIterator<[IndexTy; INDEX_INLINE_LEN]>,
+    start: usize,
+    marker: PhantomData<fn() -> (T, Dest)>,
+}
+
+impl<T, Dest, IndexTy, BackingTy, const INDEX_INLINE_LEN: usize> Iterator
+    for IntoIter<T, Dest, IndexTy, BackingTy, INDEX_INLINE_LEN>
+where
+    IndexTy: Default,
+    usize: TryFrom<IndexTy>,
+    <usize as TryFrom<IndexTy>>::Error: fmt::Debug,
+    Dest: for<'b> FromFlat<'b, BackingTy, T>,
+{
+    type Item = Dest;
+
+    #[inline]
+    fn next(&mut self) -> Option<Dest> {
+        let end = self.ends.next()?.try_into().unwrap();
+        let start = self.start;
+        self.start = end;
+        Some(Dest::from_flat(&self.data[start..end]))
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.ends.size_hint()
+    }
+}
+
+impl<T, Dest, IndexTy, BackingTy, const INDEX_INLINE_LEN: usize> ExactSizeIterator
+    for IntoIter<T, Dest, IndexTy, BackingTy, INDEX_INLINE_LEN>
+where
+    IndexTy: Default,
+    usize: TryFrom<IndexTy>,
+    <usize as TryFrom<IndexTy>>::Error: fmt::Debug,
+    Dest: for<'b> FromFlat<'b, BackingTy, T>,
+{
+}
+
+/// An iterator that removes a range of elements from a `FlatVec` and constructs a `Dest` from
+/// each of them, created by `FlatVec::drain`.
+pub struct Drain<'a, T, Dest, IndexTy, BackingTy, const INDEX_INLINE_LEN: usize>
+where
+    IndexTy: Default,
+    IndexTy: TryFrom<usize> + Copy + Sub,
+    usize: TryFrom<IndexTy>,
+    <IndexTy as TryFrom<usize>>::Error: fmt::Debug,
+    <usize as TryFrom<IndexTy>>::Error: fmt::Debug,
+    BackingTy: Copy,
+{
+    vec: &'a mut FlatVec<T, IndexTy, BackingTy, INDEX_INLINE_LEN>,
+    // The elements which will be removed on drop
+    range: Range<usize>,
+    // The elements which have not been yielded yet
+    remaining: Range<usize>,
+    marker: PhantomData<fn() -> Dest>,
+}
+
+impl<T, Dest, IndexTy, BackingTy, const INDEX_INLINE_LEN: usize> Iterator
+    for Drain<'_, T, Dest, IndexTy, BackingTy, INDEX_INLINE_LEN>
+where
+    IndexTy: Default,
+    IndexTy: TryFrom<usize> + Copy + Sub,
+    usize: TryFrom<IndexTy>,
+    <IndexTy as TryFrom<usize>>::Error: fmt::Debug,
+    <usize as TryFrom<IndexTy>>::Error: fmt::Debug,
+    BackingTy: Copy,
+    Dest: for<'b> FromFlat<'b, BackingTy, T>,
+{
+    type Item = Dest;
+
+    #[inline]
+    fn next(&mut self) -> Option<Dest> {
+        let index = self.remaining.next()?;
+        self.vec.raw_get(index).map(Dest::from_flat)
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.remaining.size_hint()
+    }
+}
+
+impl<T, Dest, IndexTy, BackingTy, const INDEX_INLINE_LEN: usize> DoubleEndedIterator
+    for Drain<'_, T, Dest, IndexTy, BackingTy, INDEX_INLINE_LEN>
+where
+    IndexTy: Default,
+    IndexTy: TryFrom<usize> + Copy + Sub,
+    usize: TryFrom<IndexTy>,
+    <IndexTy as TryFrom<usize>>::Error: fmt::Debug,
+    <usize as TryFrom<IndexTy>>::Error: fmt::Debug,
+    BackingTy: Copy,
+    Dest: for<'b> FromFlat<'b, BackingTy, T>,
+{
+    #[inline]
+    fn next_back(&mut self) -> Option<Dest> {
+        let index = self.remaining.next_back()?;
+        self.vec.raw_get(index).map(Dest::from_flat)
+    }
+}
+
+impl<T, Dest, IndexTy, BackingTy, const INDEX_INLINE_LEN: usize> ExactSizeIterator
+    for Drain<'_, T, Dest, IndexTy, BackingTy, INDEX_INLINE_LEN>
+where
+    IndexTy: Default,
+    IndexTy: TryFrom<usize> + Copy + Sub,
+    usize: TryFrom<IndexTy>,
+    <IndexTy as TryFrom<usize>>::Error: fmt::Debug,
+    <usize as TryFrom<IndexTy>>::Error: fmt::Debug,
+    BackingTy: Copy,
+    Dest: for<'b> FromFlat<'b, BackingTy, T>,
+{
+}
+
+impl<T, Dest, IndexTy, BackingTy, const INDEX_INLINE_LEN: usize> Drop
+    for Drain<'_, T, Dest, IndexTy, BackingTy, INDEX_INLINE_LEN>
+where
+    IndexTy: Default,
+    IndexTy: TryFrom<usize> + Copy + Sub,
+    usize: TryFrom<IndexTy>,
+    <IndexTy as TryFrom<usize>>::Error: fmt::Debug,
+    <usize as TryFrom<IndexTy>>::Error: fmt::Debug,
+    BackingTy: Copy,
+{
+    fn drop(&mut self) {
+        self.vec.remove_range_unchecked(self.range.clone());
+    }
+}
+
+/// An iterator that constructs a `Dest` from each element of a `FlatVec`, created by
+/// `FlatVec::iter`.
+pub struct Iter<'a, T, Dest, IndexTy, BackingTy> {
+    raw: RawIter<'a, IndexTy, BackingTy>,
+    marker: PhantomData<fn() -> (T, Dest)>,
+}
+
+impl<'a, T, Dest, IndexTy, BackingTy> Iterator for Iter<'a, T, Dest, IndexTy, BackingTy>
+where
+    IndexTy: Copy,
+    usize: TryFrom<IndexTy>,
+    <usize as TryFrom<IndexTy>>::Error: fmt::Debug,
+    Dest: FromFlat<'a, BackingTy, T>,
+{
+    type Item = Dest;
+
+    #[inline]
+    fn next(&mut self) -> Option<Dest> {
+        self.raw.next().map(Dest::from_flat)
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.raw.size_hint()
+    }
+}
+
+impl<'a, T, Dest, IndexTy, BackingTy> DoubleEndedIterator for Iter<'a, T, Dest, IndexTy, BackingTy>
+where
+    IndexTy: Copy,
+    usize: TryFrom<IndexTy>,
+    <usize as TryFrom<IndexTy>>::Error: fmt::Debug,
+    Dest: FromFlat<'a, BackingTy, T>,
+{
+    #[inline]
+    fn next_back(&mut self) -> Option<Dest> {
+        self.raw.next_back().map(Dest::from_flat)
+    }
+}
+
+impl<'a, T, Dest, IndexTy, BackingTy> ExactSizeIterator for Iter<'a, T, Dest, IndexTy, BackingTy>
+where
+    IndexTy: Copy,
+    usize: TryFrom<IndexTy>,
+    <usize as TryFrom<IndexTy>>::Error: fmt::Debug,
+    Dest: FromFlat<'a, BackingTy, T>,
+{
+}
+
+/// An iterator that constructs a `Dest` from each element of a `FlatVec` after checking its end
+/// offsets, created by `FlatVec::try_iter`.
+pub struct TryIter<'a, T, Dest, IndexTy, BackingTy> {
+    data: &'a [BackingTy],
+    ends: &'a [IndexTy],
+    // The index of the next element to yield
+    index: usize,
+    // The start offset of the next element to yield
+    start: usize,
+    marker: PhantomData<fn() -> (T, Dest)>,
+}
+
+impl<'a, T, Dest, IndexTy, BackingTy> Iterator for TryIter<'a, T, Dest, IndexTy, BackingTy>
+where
+    IndexTy: Copy,
+    usize: TryFrom<IndexTy>,
+    Dest: FromFlat<'a, BackingTy, T>,
+{
+    type Item = Result<Dest, LayoutError>;
+
+    #[inline]
+    fn next(&mut self) -> Option<Result<Dest, LayoutError>> {
+        if self.index >= self.ends.len() {
+            return None;
+        }
+        let index = self.index;
+        let element = checked_end(self.ends, index)
+            .and_then(|end| checked_slice(self.data, self.start..end, index));
+        match element {
+            Ok(element) => {
+                self.index += 1;
+                self.start += element.len();
+                Some(Ok(Dest::from_flat(element)))
+            }
+            Err(e) => {
+                self.index = self.ends.len();
+                Some(Err(e))
+            }
+        }
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let remaining = self.ends.len() - self.index;
+        (cmp::min(remaining, 1), Some(remaining))
+    }
+}
+
+/// A group of consecutive elements of a `FlatVec`, created by `FlatVec::element_chunks`.
+#[derive(Clone)]
+pub struct Chunk<'a, IndexTy, BackingTy> {
+    data: &'a [BackingTy],
+    offsets: Offsets<'a, IndexTy>,
+}
+
+impl<'a, IndexTy, BackingTy> Chunk<'a, IndexTy, BackingTy>
+where
+    IndexTy: Copy,
+    usize: TryFrom<IndexTy>,
+    <usize as TryFrom<IndexTy>>::Error: fmt::Debug,
+{
+    /// Returns the number of elements in the chunk.
+    #[inline]
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.offsets.len()
+    }
+
+    /// Returns `true` if the chunk contains no elements, which is never the case for a chunk
+    /// yielded by `ElementChunks`.
+    #[inline]
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Returns the range of the `FlatVec`'s backing storage occupied by the elements in the
+    /// chunk.
+    #[inline]
+    #[must_use]
+    pub fn data_range(&self) -> Range<usize> {
+        let start = self.offsets.start;
+        let end = match self.offsets.ends.last() {
+            Some(&end) => end.try_into().unwrap(),
+            None => start,
+        };
+        start..end
+    }
+
+    /// Returns the combined stored representation of all the elements in the chunk.
+    #[inline]
+    #[must_use]
+    pub fn as_slice(&self) -> &'a [BackingTy] {
+        &self.data[self.data_range()]
+    }
+
+    /// Returns an iterator over the stored representation of each element in the chunk.
+    #[inline]
+    pub fn raw_iter(&self) -> RawIter<'a, IndexTy, BackingTy> {
+        RawIter {
+            data: self.data,
+            offsets: self.offsets.clone(),
+        }
+    }
+
+    /// Returns an iterator over the range of the `FlatVec`'s backing storage occupied by each
+    /// element in the chunk.
+    #[inline]
+    pub fn offsets_iter(&self) -> Offsets<'a, IndexTy> {
+        self.offsets.clone()
+    }
+}
+
+/// An iterator over groups of consecutive elements of a `FlatVec`, created by
+/// `FlatVec::element_chunks`.
+pub struct ElementChunks<'a, IndexTy, BackingTy> {
+    data: &'a [BackingTy],
+    offsets: Offsets<'a, IndexTy>,
+    n: usize,
+}
+
+impl<'a, IndexTy, BackingTy> Iterator for ElementChunks<'a, IndexTy, BackingTy>
+where
+    IndexTy: Copy,
+    usize: TryFrom<IndexTy>,
+    <usize as TryFrom<IndexTy>>::Error: fmt::Debug,
+{
+    type Item = Chunk<'a, IndexTy, BackingTy>;
+
+    #[inline]
+    fn next(&mut self) -> Option<Chunk<'a, IndexTy, BackingTy>> {
+        if self.offsets.ends.is_empty() {
+            return None;
+        }
+        let (ends, rest) = self
+            .offsets
+            .ends
+            .split_at(cmp::min(self.n, self.offsets.ends.len()));
+        let chunk = Offsets {
+            ends,
+            start: self.offsets.start,
+        };
+        self.offsets = Offsets {
+            ends: rest,
+            start: ends[ends.len() - 1].try_into().unwrap(),
+        };
+        Some(Chunk {
+            data: self.data,
+            offsets: chunk,
+        })
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let remaining = self.offsets.ends.len();
+        let len = match remaining % self.n {
+            0 => remaining / self.n,
+            _ => remaining / self.n + 1,
+        };
+        (len, Some(len))
+    }
+}
+
+impl<IndexTy, BackingTy> ExactSizeIterator for ElementChunks<'_, IndexTy, BackingTy>
+where
+    IndexTy: Copy,
+    usize: TryFrom<IndexTy>,
+    <usize as TryFrom<IndexTy>>::Error: fmt::Debug,
+{
+}
+
+/// An iterator over the range of backing storage occupied by each element of a `FlatVec`,
+/// created by `FlatVec::offsets_iter`.
+#[derive(Clone)]
+pub struct Offsets<'a, IndexTy> {
+    // The end offsets of the elements not yet yielded
+    ends: &'a [IndexTy],
+    // The start offset of the first element not yet yielded
+    start: usize,
+}
+
+impl<IndexTy> Offsets<'_, IndexTy>
+where
+    IndexTy: Copy,
+    usize: TryFrom<IndexTy>,
+    <usize as TryFrom<IndexTy>>::Error: fmt::Debug,
+{
+    // Returns the offset where the index'th remaining element starts, which is also the end of
+    // the previous element. This is the one place which knows how elements are laid out.
+    #[inline]
+    pub(crate) fn start_of(&self, index: usize) -> usize {
+        if index == 0 {
+            self.start
+        } else {
+            self.ends[index - 1].try_into().unwrap()
+        }
+    }
+
+    // Returns the range occupied by the index'th remaining element
+    #[inline]
+    pub(crate) fn range_of(&self, index: usize) -> Range<usize> {
+        self.start_of(index)..self.start_of(index + 1)
+    }
+}
+
+impl<IndexTy> Iterator for Offsets<'_, IndexTy>
+where
+    IndexTy: Copy,
+    usize: TryFrom<IndexTy>,
+    <usize as TryFrom<IndexTy>>::Error: fmt::Debug,
+{
+    type Item = Range<usize>;
+
+    #[inline]
+    fn next(&mut self) -> Option<Range<usize>> {
+        let (&end, rest) = self.ends.split_first()?;
+        let end = end.try_into().unwrap();
+        let start = self.start;
+        self.ends = rest;
+        self.start = end;
+        Some(start..end)
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.ends.len(), Some(self.ends.len()))
+    }
+}
+
+impl<IndexTy> DoubleEndedIterator for Offsets<'_, IndexTy>
+where
+    IndexTy: Copy,
+    usize: TryFrom<IndexTy>,
+    <usize as TryFrom<IndexTy>>::Error: fmt::Debug,
+{
+    #[inline]
+    fn next_back(&mut self) -> Option<Range<usize>> {
+        let (&end, rest) = self.ends.split_last()?;
+        let end = end.try_into().unwrap();
+        let start = match rest.last() {
+            Some(&start) => start.try_into().unwrap(),
+            None => self.start,
+        };
+        self.ends = rest;
+        Some(start..end)
+    }
+}
+
+impl<IndexTy> ExactSizeIterator for Offsets<'_, IndexTy>
+where
+    IndexTy: Copy,
+    usize: TryFrom<IndexTy>,
+    <usize as TryFrom<IndexTy>>::Error: fmt::Debug,
+{
+}
+
+/// Formats the elements of a `FlatVec` as a list, created by `FlatVec::debug_elements`.
+pub struct DebugElements<'a, T, Dest, IndexTy, BackingTy> {
+    data: &'a [BackingTy],
+    offsets: Offsets<'a, IndexTy>,
+    marker: PhantomData<fn() -> (T, Dest)>,
+}
+
+impl<'a, T, Dest, IndexTy, BackingTy> fmt::Debug for DebugElements<'a, T, Dest, IndexTy, BackingTy>
+where
+    IndexTy: Copy,
+    usize: TryFrom<IndexTy>,
+    <usize as TryFrom<IndexTy>>::Error: fmt::Debug,
+    Dest: FromFlat<'a, BackingTy, T> + fmt::Debug,
+{
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        let data = self.data;
+        fmt.debug_list()
+            .entries(
+                self.offsets
+                    .clone()
+                    .map(|range| Dest::from_flat(&data[range])),
+            )
+            .finish()
+    }
+}
+
+/// An iterator over the stored representation of each element of a `FlatVec`, created by
+/// `FlatVec::raw_iter`.
+pub struct RawIter<'a, IndexTy, BackingTy> {
+    data: &'a [BackingTy],
+    offsets: Offsets<'a, IndexTy>,
+}
+
+impl<'a, IndexTy, BackingTy> Iterator for RawIter<'a, IndexTy, BackingTy>
+where
+    IndexTy: Copy,
+    usize: TryFrom<IndexTy>,
+    <usize as TryFrom<IndexTy>>::Error: fmt::Debug,
+{
+    type Item = &'a [BackingTy];
+
+    #[inline]
+    fn next(&mut self) -> Option<&'a [BackingTy]> {
+        self.offsets.next().map(|range| &self.data[range])
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.offsets.size_hint()
+    }
+}
+
+impl<'a, IndexTy, BackingTy> DoubleEndedIterator for RawIter<'a, IndexTy, BackingTy>
+where
+    IndexTy: Copy,
+    usize: TryFrom<IndexTy>,
+    <usize as TryFrom<IndexTy>>::Error: fmt::Debug,
+{
+    #[inline]
+    fn next_back(&mut self) -> Option<&'a [BackingTy]> {
+        self.offsets.next_back().map(|range| &self.data[range])
+    }
+}
+
+impl<IndexTy, BackingTy> ExactSizeIterator for RawIter<'_, IndexTy, BackingTy>
+where
+    IndexTy: Copy,
+    usize: TryFrom<IndexTy>,
+    <usize as TryFrom<IndexTy>>::Error: fmt::Debug,
+{
+}
+
+fn binary_search_indices<F>(len: usize, mut f: F) -> Result<usize, usize>
+where
+    F: FnMut(usize) -> Ordering,
+{
+    let mut low = 0;
+    let mut high = len;
+    while low < high {
+        let mid = low + (high - low) / 2;
+        match f(mid) {
+            Ordering::Less => low = mid + 1,
+            Ordering::Greater => high = mid,
+            Ordering::Equal => return Ok(mid),
+        }
+    }
+    Err(low)
+}
+
+// Panics if factor cannot be used as a growth factor, shared by every way of setting one
+#[inline]
+fn check_growth_factor(factor: f32) {
+    assert!(
+        factor.is_finite() && factor >= 1.0,
+        "growth factor (is {}) should be finite and >= 1",
+        factor
+    );
+}
+
+// Returns the capacity to grow backing storage of capacity len to, so that it fits at least
+// requested more BackingTy
+#[inline]
+fn grown_capacity(len: usize, requested: usize, factor: f32) -> usize {
+    let grown = (len as f64 * f64::from(factor)) as usize;
+    cmp::max(len.saturating_add(requested), grown)
+}
+
+// Converts any range of element indices to a Range, panicking like slice indexing does if it is
+// out of bounds
+fn resolve_range<R>(range: R, len: usize) -> Range<usize>
+where
+    R: RangeBounds<usize>,
+{
+    let start = match range.start_bound() {
+        Bound::Included(&start) => start,
+        Bound::Excluded(&start) => start
+            .checked_add(1)
+            .expect("attempted to index slice from after maximum usize"),
+        Bound::Unbounded => 0,
+    };
+    let end = match range.end_bound() {
+        Bound::Included(&end) => end
+            .checked_add(1)
+            .expect("attempted to index slice up to maximum usize"),
+        Bound::Excluded(&end) => end,
+        Bound::Unbounded => len,
+    };
+    assert!(
+        start <= end,
+        "slice index starts at {} but ends at {}",
+        start,
+        end
+    );
+    assert!(
+        end <= len,
+        "range end index {} out of range for slice of length {}",
+        end,
+        len
+    );
+    start..end
+}
+
+// Converts the index'th end offset to a usize, or reports that it cannot be
+#[inline]
+fn checked_end<IndexTy>(ends: &[IndexTy], index: usize) -> Result<usize, LayoutError>
+where
+    IndexTy: Copy,
+    usize: TryFrom<IndexTy>,
+{
+    usize::try_from(ends[index]).map_err(|_| LayoutError::InvalidEnd { index })
+}
+
+// Returns the range of data occupied by the index'th element, or reports why the range is invalid
+#[inline]
+fn checked_slice<BackingTy>(
+    data: &[BackingTy],
+    range: Range<usize>,
+    index: usize,
+) -> Result<&[BackingTy], LayoutError> {
+    if range.end < range.start {
+        return Err(LayoutError::UnsortedEnds { index });
+    }
+    data.get(range.clone()).ok_or(LayoutError::EndOutOfBounds {
+        index,
+        end: range.end,
+        capacity: data.len(),
+    })
+}
+
+// Checks that ends describes elements which are all stored in the first data_len of capacity
+// BackingTy, which is what every FlatVec relies on to never index out of bounds
+pub(crate) fn validate_layout<IndexTy>(
+    ends: &[IndexTy],
+    data_len: usize,
+    capacity: usize,
+) -> Result<(), LayoutError>
+where
+    IndexTy: Copy,
+    usize: TryFrom<IndexTy>,
+{
+    if data_len > capacity {
+        return Err(LayoutError::DataLenOutOfBounds { data_len, capacity });
+    }
+    let mut previous = 0;
+    for (index, &end) in ends.iter().enumerate() {
+        let end = usize::try_from(end).map_err(|_| LayoutError::InvalidEnd { index })?;
+        if end < previous {
+            return Err(LayoutError::UnsortedEnds { index });
+        }
+        previous = end;
+    }
+    if previous != data_len {
+        return Err(LayoutError::LastEndMismatch {
+            last_end: previous,
+            data_len,
+        });
+    }
+    Ok(())
+}
+
+/// The error returned when the parts of a `FlatVec` do not describe a valid `FlatVec`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum LayoutError {
+    /// `data_len` is greater than the length of the backing storage.
+    DataLenOutOfBounds { data_len: usize, capacity: usize },
+    /// The end offset at `index` cannot be converted to a `usize`.
+    InvalidEnd { index: usize },
+    /// The end offset at `index` is less than the end offset before it.
+    UnsortedEnds { index: usize },
+    /// The last end offset is not equal to `data_len`.
+    LastEndMismatch { last_end: usize, data_len: usize },
+    /// The end offset at `index` is greater than the length of the backing storage.
+    EndOutOfBounds {
+        index: usize,
+        end: usize,
+        capacity: usize,
+    },
+}
+
+impl fmt::Display for LayoutError {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            LayoutError::DataLenOutOfBounds { data_len, capacity } => write!(
+                fmt,
+                "data_len is {} but the backing storage has a length of {}",
+                data_len, capacity
+            ),
+            LayoutError::InvalidEnd { index } => {
+                write!(fmt, "end offset {} cannot be represented by usize", index)
+            }
+            LayoutError::UnsortedEnds { index } => write!(
+                fmt,
+                "end offset {} is less than the end offset before it",
+                index
+            ),
+            LayoutError::LastEndMismatch { last_end, data_len } => write!(
+                fmt,
+                "the last end offset is {} but data_len is {}",
+                last_end, data_len
+            ),
+            LayoutError::EndOutOfBounds {
+                index,
+                end,
+                capacity,
+            } => write!(
+                fmt,
+                "end offset {} is {} but the backing storage has a length of {}",
+                index, end, capacity
+            ),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for LayoutError {}
+
+/// The error returned when an element's end offset cannot be represented as the `IndexTy` of a
+/// `FlatVec`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct CapacityError {
+    offset: usize,
+}
+
+impl CapacityError {
+    /// Returns the end offset that did not fit in the `IndexTy`.
+    #[inline]
+    #[must_use]
+    pub fn offset(&self) -> usize {
+        self.offset
+    }
+}
+
+impl fmt::Display for CapacityError {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            fmt,
+            "end offset {} cannot be represented by the index type",
+            self.offset
+        )
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for CapacityError {}
+
+/// The error returned when an element is requested at an index that is out of bounds.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct IndexError {
+    index: usize,
+    len: usize,
+}
+
+impl IndexError {
+    /// Returns the index that was requested.
+    #[inline]
+    #[must_use]
+    pub fn index(&self) -> usize {
+        self.index
+    }
+
+    /// Returns the length of the `FlatVec` at the time of the request.
+    #[inline]
+    #[must_use]
+    pub fn vec_len(&self) -> usize {
+        self.len
+    }
+}
+
+impl fmt::Display for IndexError {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            fmt,
+            "index {} is out of bounds for a FlatVec of length {}",
+            self.index, self.len
+        )
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for IndexError {}
+
+// On the surface, this Box juggling seems like a re-implementation of std::vec::Vec.
+// The difference is our allocated memory is always default-initialized, so that we can implement
+// Storage::allocate, which returns a slice of BackingTy that is not yet used for an object in the
+// FlatVec. So just like Vec we have a length and capacity, but unlike Vec the objects beyond the
+// container's length are guaranteed to be initialized and thus can be accessed from safe code.
+/// A wrapper over the innards of a `FlatVec` which exposes mutating operations which cannot
+/// corrupt other elements when inserting a new element.
+pub struct Storage<'a, BackingTy> {
+    data: &'a mut Box<[BackingTy]>,
+    data_len: &'a mut usize,
+    // The data_len when this Storage was handed out
+    start: usize,
+    growth_factor: f32,
+}
+
+impl<BackingTy> Storage<'_, BackingTy> {
+    /// Returns the number of `BackingTy` that have been inserted into this flattened object so
+    /// far.
+    #[inline]
+    #[must_use]
+    pub fn offset(&self) -> usize {
+        *self.data_len - self.start
+    }
+}
+
+impl<BackingTy> Storage<'_, BackingTy>
+where
+    BackingTy: Default,
+{
+    #[inline(never)]
+    fn allocate_slow_path(&mut self, requested: usize) {
+        let mut data = mem::take(self.data).into_vec();
+        let target = grown_capacity(data.len(), requested, self.growth_factor);
+        data.resize_with(target, BackingTy::default);
+        *self.data = data.into_boxed_slice();
+    }
+
+    /// Returns a `Default` slice of `BackingTy` that will be considered part of this flattened
+    /// object.
+    ///
+    /// Note that even if you do not use part of this slice, the whole slice will be presented to a
+    /// `FromFlat` implementation. This function may be called multiple times in a single
+    /// `IntoFlat` implementation or combined with `Storage::extend` if a flattened object is
+    /// complex, but it is significantly more efficient to use a single `Storage::allocate` call
+    /// where possible.
+    #[inline]
+    pub fn allocate(&mut self, requested: usize) -> &mut [BackingTy] {
+        self.reserve(requested);
+        let old_len = *self.data_len;
+        *self.data_len += requested;
+        &mut self.data[old_len..old_len + requested]
+    }
+
+    /// Reserves capacity for at least `len` additional `BackingTy`.
+    #[inline]
+    pub fn reserve(&mut self, requested: usize) {
+        if self.data.len() < *self.data_len + requested {
+            self.allocate_slow_path(requested);
+        }
+    }
+
+    /// Tries to reserve capacity for at least `len` additional `BackingTy`, and returns an error
+    /// instead of aborting if the allocation fails.
+    ///
+    /// If this returns an error, the backing storage is unchanged.
+    #[inline]
+    pub fn try_reserve(&mut self, requested: usize) -> Result<(), TryReserveError> {
+        if self.data.len() >= self.data_len.saturating_add(requested) {
+            return Ok(());
+        }
+        let mut data = mem::take(self.data).into_vec();
+        let target = grown_capacity(data.len(), requested, self.growth_factor);
+        let result = data.try_reserve_exact(target - data.len());
+        if result.is_ok() {
+            // Use all of the capacity, so that converting to a boxed slice does not reallocate
+            data.resize_with(data.capacity(), BackingTy::default);
+        }
+        *self.data = data.into_boxed_slice();
+        result
+    }
+
+    /// Inserts the `BackingTy` yielded by `iter`.
+    ///
+    /// In general, this is ~2x slower than calling `allocate` when the exact size of the inserted
+    /// object is known.
+    #[inline]
+    pub fn extend<Iter>(&mut self, iter: Iter)
+    where
+        Iter: IntoIterator<Item = BackingTy>,
+    {
+        let mut iter = iter.into_iter();
+
+        // When the iterator provides a precise size hint, use the allocate interface.
+        // This path is ~5x faster than the one below
+        let hint = iter.size_hint();
+        if Some(hint.0) == hint.1 {
+            let data = self.allocate(hint.0);
+            let mut written = 0;
+            for (dst, src) in data.iter_mut().zip(&mut iter) {
+                *dst = src;
+                written += 1;
+            }
+            // Size hints are not trusted, the iterator may have yielded fewer elements than it
+            // said it would, or it may have more left.
+            if written < hint.0 {
+                *self.data_len -= hint.0 - written;
+                return;
+            }
+        }
+
+        // Insert as many elements as possible into already-allocated space
+        for (out, val) in self.data.iter_mut().skip(*self.data_len).zip(&mut iter) {
+            *out = val;
+            *self.data_len += 1;
+        }
+
+        // If there are elements remaining in the iterator, allocate space for them
+        if let Some(val) = iter.next() {
+            let mut data = mem::take(self.data).into_vec();
+            data.push(val);
+            *self.data_len += 1;
+
+            for val in iter {
+                data.push(val);
+                *self.data_len += 1;
+            }
+
+            if data.capacity() > data.len() {
+                data.resize_with(data.capacity(), BackingTy::default);
+            }
+            let mut data = data.into_boxed_slice();
+            mem::swap(self.data, &mut data);
+            mem::forget(data);
+        }
+    }
+}
+
+impl<BackingTy> Storage<'_, BackingTy>
+where
+    BackingTy: Default + Copy,
+{
+    /// Inserts a copy of every `BackingTy` in `slice`.
+    ///
+    /// This is equivalent to `allocate(slice.len()).copy_from_slice(slice)`.
+    #[inline]
+    pub fn extend_from_slice(&mut self, slice: &[BackingTy]) {
+        self.allocate(slice.len()).copy_from_slice(slice);
+    }
+}
+
+/// Implement `IntoFlat<Flattened> for Source` to insert a `Source` into a `FlatVec<Flattened>`
+pub trait IntoFlat<BackingTy, Flattened> {
+    fn into_flat(self, storage: Storage<BackingTy>);
+}
+
+/// Implement `FromFlat<'a, Flattened> for Dest` to get a `Dest` from a `FlatVec<Flattened>`
+pub trait FromFlat<'a, BackingTy, Flattened> {
+    fn from_flat(data: &'a [BackingTy]) -> Self;
+}
+
+/// Implement `FromFlatInto<Flattened> for Dest` to overwrite an existing `Dest` with an element
+/// of a `FlatVec<Flattened>`, so that its allocations can be reused
+pub trait FromFlatInto<BackingTy, Flattened> {
+    fn from_flat_into(data: &[BackingTy], out: &mut Self);
+}
+
+/// Implement `TryFromFlat<'a, Flattened> for Dest` to fallibly get a `Dest` from a
+/// `FlatVec<Flattened>`, for when the backing storage may not be a valid `Dest`
+pub trait TryFromFlat<'a, BackingTy, Flattened>: Sized {
+    type Error;
+    fn try_from_flat(data: &'a [BackingTy]) -> Result<Self, Self::Error>;
+}
+
+impl IntoFlat<u8, String> for &str {
+    #[inline]
+    fn into_flat(self, mut store: Storage<u8>) {
+        store.extend_from_slice(self.as_bytes());
+    }
+}
+
+impl<'a> FromFlat<'a, u8, String> for &'a str {
+    #[inline]
+    fn from_flat(data: &'a [u8]) -> &'a str {
+        str::from_utf8(data).unwrap()
+    }
+}
+
+impl<'a> FromFlat<'a, u8, String> for Cow<'a, str> {
+    #[inline]
+    fn from_flat(data: &'a [u8]) -> Cow<'a, str> {
+        Cow::Borrowed(str::from_utf8(data).unwrap())
+    }
+}
+
+impl<'a> TryFromFlat<'a, u8, String> for &'a str {
+    type Error = str::Utf8Error;
+    #[inline]
+    fn try_from_flat(data: &'a [u8]) -> Result<&'a str, str::Utf8Error> {
+        str::from_utf8(data)
+    }
+}
+
+impl FromFlatInto<u8, String> for String {
+    #[inline]
+    fn from_flat_into(data: &[u8], out: &mut String) {
+        out.clear();
+        out.push_str(str::from_utf8(data).unwrap());
+    }
+}
+
+impl<Iter, BackingTy> IntoFlat<BackingTy, Vec<BackingTy>> for Iter
+where
+    Iter: IntoIterator<Item = BackingTy>,
+    BackingTy: Default,
+{
+    #[inline]
+    fn into_flat(self, mut store: Storage<BackingTy>) {
+        store.extend(self);
+    }
+}
+
+impl<'a, BackingTy> FromFlat<'a, BackingTy, Vec<BackingTy>> for &'a [BackingTy] {
+    #[inline]
+    fn from_flat(data: &'a [BackingTy]) -> &'a [BackingTy] {
+        data
+    }
+}
+
+impl<'a, BackingTy> FromFlat<'a, BackingTy, Vec<BackingTy>> for Cow<'a, [BackingTy]>
+where
+    BackingTy: Clone,
+{
+    #[inline]
+    fn from_flat(data: &'a [BackingTy]) -> Cow<'a, [BackingTy]> {
+        Cow::Borrowed(data)
+    }
+}
+
+impl<BackingTy> FromFlatInto<BackingTy, Vec<BackingTy>> for Vec<BackingTy>
+where
+    BackingTy: Clone,
+{
+    #[inline]
+    fn from_flat_into(data: &[BackingTy], out: &mut Vec<BackingTy>) {
+        out.clear();
+        out.extend_from_slice(data);
+    }
+}
+
+impl<BackingTy, const M: usize> IntoFlat<BackingTy, [BackingTy; M]> for [BackingTy; M]
+where
+    BackingTy: Default + Copy,
+{
+    #[inline]
+    fn into_flat(self, mut store: Storage<BackingTy>) {
+        store.extend_from_slice(&self);
+    }
+}
+
+impl<'a, BackingTy, const M: usize> FromFlat<'a, BackingTy, [BackingTy; M]> for [BackingTy; M]
+where
+    BackingTy: Copy,
+{
+    #[inline]
+    fn from_flat(data: &'a [BackingTy]) -> [BackingTy; M] {
+        data.try_into().unwrap()
+    }
+}
+
+impl<'a, BackingTy, const M: usize> TryFromFlat<'a, BackingTy, [BackingTy; M]> for [BackingTy; M]
+where
+    BackingTy: Copy,
+{
+    type Error = array::TryFromSliceError;
+    #[inline]
+    fn try_from_flat(data: &'a [BackingTy]) -> Result<[BackingTy; M], array::TryFromSliceError> {
+        data.try_into()
+    }
+}
+
+/// Each item is stored as its length, encoded as an unsigned LEB128 varint, followed by its
+/// contents. The varint holds 7 bits of the length per byte, least significant bits first, and
+/// the high bit of every byte except the last one is set.
+impl<'b, Iter> IntoFlat<u8, Vec<Vec<u8>>> for Iter
+where
+    Iter: IntoIterator<Item = &'b [u8]>,
+{
+    #[inline]
+    fn into_flat(self, mut store: Storage<u8>) {
+        for item in self {
+            let mut len = item.len();
+            while len >= 0x80 {
+                store.extend_from_slice(&[len as u8 | 0x80]);
+                len >>= 7;
+            }
+            store.extend_from_slice(&[len as u8]);
+            store.extend_from_slice(item);
+        }
+    }
+}
+
+impl<'a> FromFlat<'a, u8, Vec<Vec<u8>>> for LengthPrefixed<'a> {
+    #[inline]
+    fn from_flat(data: &'a [u8]) -> LengthPrefixed<'a> {
+        LengthPrefixed { data }
+    }
+}
+
+impl<'a> FromFlat<'a, u8, Vec<Vec<u8>>> for Vec<Vec<u8>> {
+    #[inline]
+    fn from_flat(data: &'a [u8]) -> Vec<Vec<u8>> {
+        LengthPrefixed { data }.map(<[u8]>::to_vec).collect()
+    }
+}
+
+/// An iterator over the items of an element of a `FlatVec<Vec<Vec<u8>>>`, which are stored with
+/// length prefixes.
+///
+/// # Panics
+///
+/// Iteration panics if the element is not a valid sequence of length-prefixed items.
+#[derive(Clone, Debug)]
+pub struct LengthPrefixed<'a> {
+    data: &'a [u8],
+}
+
+impl<'a> Iterator for LengthPrefixed<'a> {
+    type Item = &'a [u8];
+
+    #[inline]
+    fn next(&mut self) -> Option<&'a [u8]> {
+        if self.data.is_empty() {
+            return None;
+        }
+        let mut len = 0usize;
+        let mut shift = 0;
+        loop {
+            let (&byte, rest) = self.data.split_first().expect("truncated length prefix");
+            self.data = rest;
+            let bits = usize::from(byte & 0x7f);
+            assert!(
+                shift < usize::BITS && (bits << shift) >> shift == bits,
+                "length prefix overflows usize"
+            );
+            len |= bits << shift;
+            shift += 7;
+            if byte & 0x80 == 0 {
+                break;
+            }
+        }
+        assert!(len <= self.data.len(), "item extends past the element");
+        let (item, rest) = self.data.split_at(len);
+        self.data = rest;
+        Some(item)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::{
+        format,
+        string::{String, ToString},
+        vec,
+        vec::Vec,
+    };
+
+    #[test]
+    fn push_get() {
+        let mut names: FlatVec<String, usize, u8, 3> = FlatVec::new();
+        assert!(names.is_empty());
+        assert_eq!(names.len(), 0);
+        assert_eq!(names.data_len(), 0);
+
+        names.push("Cerryl");
+        assert_eq!(names.len(), 1);
+        assert!(!names.is_empty());
+        assert_eq!(names.data_len(), 6);
+
+        names.push("Jeslek");
+        assert_eq!(names.len(), 2);
+        assert_eq!(names.data_len(), 12);
+
+        assert_eq!(names.get(0), Some("Cerryl"));
+        assert_eq!(names.get(1), Some("Jeslek"));
+        assert_eq!(names.get::<&str>(2), None);
+
+        assert_eq!(names.get(0), Some("Cerryl"));
+        assert_eq!(names.get(1), Some("Jeslek"));
+        assert_eq!(names.get::<&str>(2), None);
+
+        names.clear();
+        assert_eq!(names.len(), 0);
+        assert!(names.is_empty());
+        assert_eq!(names.data_len(), 0);
+    }
+
+    #[test]
+    fn first_last() {
+        let mut names: FlatVec<String, usize, u8, 3> = FlatVec::new();
+        assert_eq!(names.first::<&str>(), None);
+        assert_eq!(names.last::<&str>(), None);
+
+        names.push("Cerryl");
+        assert_eq!(names.first(), Some("Cerryl"));
+        assert_eq!(names.last(), Some("Cerryl"));
+
+        names.push("Jeslek");
+        assert_eq!(names.first(), Some("Cerryl"));
+        assert_eq!(names.last(), Some("Jeslek"));
+    }
+
+    #[test]
+    fn element_len() {
+        let names: FlatVec<String, usize, u8, 3> = ["Cerryl", "", "Anya"].iter().copied().collect();
+        assert_eq!(names.element_len(0), Some(6));
+        assert_eq!(names.element_len(1), Some(0));
+        assert_eq!(names.element_len(2), Some(4));
+        assert_eq!(names.element_len(3), None);
+    }
+
+    #[test]
+    fn byte_range() {
+        let names: FlatVec<String, usize, u8, 3> = ["Cerryl", "", "Anya"].iter().copied().collect();
+        assert_eq!(names.byte_range(0), Some(0..6));
+        assert_eq!(names.byte_range(1), Some(6..6));
+        assert_eq!(names.byte_range(2), Some(6..10));
+        assert_eq!(names.byte_range(3), None);
+    }
+
+    #[test]
+    fn index() {
+        let names: FlatVec<String, usize, u8, 3> = ["Cerryl", "", "Anya"].iter().copied().collect();
+        assert_eq!(&names[0], b"Cerryl");
+        assert_eq!(&names[1], b"");
+        assert_eq!(&names[2], b"Anya");
+    }
+
+    #[test]
+    #[should_panic(expected = "index out of bounds")]
+    fn index_out_of_bounds() {
+        let names: FlatVec<String, usize, u8, 3> = ["Cerryl"].iter().copied().collect();
+        let _ = &names[1];
+    }
+
+    #[test]
+    fn position() {
+        let names: FlatVec<String, usize, u8, 3> =
+            ["Cerryl", "Jeslek", "Anya"].iter().copied().collect();
+        assert_eq!(names.position(|n| n.starts_with(b"J")), Some(1));
+        assert_eq!(names.position(|n| n.is_empty()), None);
+        assert!(names.contains_slice(b"Anya"));
+        assert!(!names.contains_slice(b"Any"));
+    }
+
+    #[test]
+    fn reverse() {
+        let mut names: FlatVec<String, usize, u8, 3> =
+            ["Cerryl", "", "Anya", "Jeslek"].iter().copied().collect();
+        names.reverse();
+        assert_eq!(
+            names.iter().collect::<Vec<&str>>(),
+            ["Jeslek", "Anya", "", "Cerryl"]
+        );
+        names.push("Kiella");
+        assert_eq!(names.get(4), Some("Kiella"));
+
+        let mut one: FlatVec<String, usize, u8, 3> = ["Cerryl"].iter().copied().collect();
+        one.reverse();
+        assert_eq!(one.get(0), Some("Cerryl"));
+    }
+
+    #[test]
+    fn rotate() {
+        let mut names: FlatVec<String, usize, u8, 3> =
+            ["Cerryl", "", "Anya", "Jeslek"].iter().copied().collect();
+        names.rotate_left(1);
+        assert_eq!(
+            names.iter().collect::<Vec<&str>>(),
+            ["", "Anya", "Jeslek", "Cerryl"]
+        );
+        names.rotate_right(2);
+        assert_eq!(
+            names.iter().collect::<Vec<&str>>(),
+            ["Jeslek", "Cerryl", "", "Anya"]
+        );
+        names.rotate_left(0);
+        names.rotate_left(4);
+        assert_eq!(
+            names.iter().collect::<Vec<&str>>(),
+            ["Jeslek", "Cerryl", "", "Anya"]
+        );
+        names.push("Kiella");
+        assert_eq!(names.get(4), Some("Kiella"));
+    }
+
+    #[test]
+    #[should_panic]
+    fn rotate_past_len() {
+        let mut names: FlatVec<String, usize, u8, 3> = ["Cerryl"].iter().copied().collect();
+        names.rotate_left(2);
+    }
+
+    #[test]
+    fn data_as_slice() {
+        let mut names: FlatVec<String, usize, u8, 3> = ["Cerryl", "Anya"].iter().copied().collect();
+        names.reserve_data(16);
+        assert_eq!(names.data_as_slice(), b"CerrylAnya");
+        names.data_as_mut_slice().make_ascii_uppercase();
+        assert_eq!(names.iter().collect::<Vec<&str>>(), ["CERRYL", "ANYA"]);
+    }
+
+    #[test]
+    fn try_get_checked() {
+        let names: FlatVec<String, usize, u8, 3> = ["Cerryl", "Anya"].iter().copied().collect();
+        assert_eq!(names.try_get_checked(1), Ok("Anya"));
+        let err = names.try_get_checked::<&str>(5).unwrap_err();
+        assert_eq!((err.index(), err.vec_len()), (5, 2));
+        assert_eq!(
+            err.to_string(),
+            "index 5 is out of bounds for a FlatVec of length 2"
+        );
+    }
+
+    #[test]
+    fn aggregates() {
+        let names: FlatVec<String, usize, u8, 3> =
+            ["Cerryl", "Anya", "Jeslek"].iter().copied().collect();
+        assert_eq!(names.count_by(|name| name.len() > 4), 2);
+        assert_eq!(names.total_element_bytes(), 16);
+        assert_eq!(
+            names.fold_raw(0, |longest, name| longest.max(name.len())),
+            6
+        );
+    }
+
+    #[test]
+    fn try_reserve() {
+        let mut names: FlatVec<String, usize, u8, 3> = ["Cerryl"].iter().copied().collect();
+        names.try_reserve(10).unwrap();
+        assert!(names.data_capacity() >= 16);
+        assert!(names.ends.capacity() >= 11);
+        assert!(names.try_reserve(usize::MAX).is_err());
+        assert_eq!(names.len(), 1);
+        assert_eq!(names.get(0), Some("Cerryl"));
+        for name in ["Anya", "Kerrick", "Yaev"] {
+            names.push(name);
+        }
+        assert_eq!(names.get(3), Some("Yaev"));
+    }
+
+    #[test]
+    fn try_reserve_data() {
+        let mut names: FlatVec<String, usize, u8, 3> = ["Cerryl"].iter().copied().collect();
+        names.try_reserve_data(10).unwrap();
+        assert!(names.data_capacity() >= 16);
+        assert!(names.try_reserve_data(usize::MAX).is_err());
+        assert!(names.data_capacity() >= 16);
+        assert_eq!(names.get(0), Some("Cerryl"));
+        names.push("Anya");
+        assert_eq!(names.get(1), Some("Anya"));
+    }
+
+    #[test]
+    fn byte_vec() {
+        let names: ByteVec<String> = ["Cerryl", "Anya"].iter().copied().collect();
+        let same: FlatVec<String, usize, u8, 3> = names.clone();
+        assert_eq!(names, same);
+    }
+
+    #[test]
+    fn builder() {
+        let mut names: FlatVec<String, usize, u8, 3> = FlatVec::builder()
+            .data_capacity(16)
+            .element_capacity(8)
+            .growth_factor(1.5)
+            .build();
+        assert_eq!(names.data_capacity(), 16);
+        assert!(names.ends_capacity() >= 8);
+        assert_eq!(names.growth_factor(), 1.5);
+        names.push("Cerryl");
+        assert_eq!(names.get(0), Some("Cerryl"));
+    }
+
+    #[test]
+    #[should_panic]
+    fn builder_bad_growth_factor() {
+        let _ = FlatVec::<String, usize, u8, 3>::builder().growth_factor(0.5);
+    }
+
+    #[test]
+    fn debug_elements() {
+        let names: FlatVec<String, usize, u8, 3> = ["Cerryl", "Anya"].iter().copied().collect();
+        assert_eq!(
+            format!("{:?}", names.debug_elements::<&str>()),
+            r#"["Cerryl", "Anya"]"#
+        );
+        assert_eq!(
+            format!(
+                "{:?}",
+                FlatVec::<String, usize, u8, 3>::new().debug_elements::<&str>()
+            ),
+            "[]"
+        );
+    }
+
+    #[test]
+    fn get_many() {
+        let names: FlatVec<String, usize, u8, 3> =
+            ["Cerryl", "Anya", "Jeslek"].iter().copied().collect();
+        assert_eq!(
+            names.get_many([2, 0, 2]),
+            Some(["Jeslek", "Cerryl", "Jeslek"])
+        );
+        assert_eq!(names.get_many::<&str, 0>([]), Some([]));
+        assert_eq!(names.get_many::<&str, 2>([1, 3]), None);
+    }
+
+    #[test]
+    fn get_mut() {
+        let mut names: FlatVec<String, usize, u8, 3> =
+            ["Cerryl", "Anya", "Jeslek"].iter().copied().collect();
+        names.get_mut(1).unwrap().make_ascii_uppercase();
+        names.last_mut().unwrap()[0] = b'B';
+        assert_eq!(
+            names.iter().collect::<Vec<&str>>(),
+            ["Cerryl", "ANYA", "Beslek"]
+        );
+        assert_eq!(names.get_mut(3), None);
+        names.clear();
+        assert_eq!(names.last_mut(), None);
+    }
+
+    #[test]
+    fn ord() {
+        let make =
+            |names: &[&str]| -> FlatVec<String, usize, u8, 3> { names.iter().copied().collect() };
+        assert!(make(&["Anya", "Cerryl"]) < make(&["Anya", "Jeslek"]));
+        assert!(make(&["Any", "aCerryl"]) < make(&["Anya", "Cerryl"]));
+        assert!(make(&["Anya"]) < make(&["Anya", ""]));
+        assert_eq!(make(&["Anya"]).cmp(&make(&["Anya"])), Ordering::Equal);
+        assert!(make(&[]) < make(&[""]));
+    }
+
+    #[test]
+    fn offsets_iter() {
+        let names: FlatVec<String, usize, u8, 3> = ["Cerryl", "", "Anya"].iter().copied().collect();
+        assert_eq!(
+            names.offsets_iter().collect::<Vec<_>>(),
+            [0..6, 6..6, 6..10]
+        );
+        assert_eq!(
+            names.offsets_iter().rev().collect::<Vec<_>>(),
+            [6..10, 6..6, 0..6]
+        );
+        let mut offsets = names.offsets_iter();
+        assert_eq!(offsets.next(), Some(0..6));
+        assert_eq!(offsets.next_back(), Some(6..10));
+        assert_eq!(offsets.len(), 1);
+        assert_eq!(offsets.next_back(), Some(6..6));
+        assert_eq!(offsets.next(), None);
+
+        assert_eq!(
+            names.enumerate_raw().collect::<Vec<_>>(),
+            [(0, &b"Cerryl"[..]), (1, b""), (2, b"Anya")]
+        );
+    }
+
+    #[test]
+    fn iter_with_raw() {
+        let names: FlatVec<String, usize, u8, 3> = ["Cerryl", "", "Anya"].iter().copied().collect();
+        assert_eq!(
+            names.iter_with_raw().collect::<Vec<_>>(),
+            [(&b"Cerryl"[..], "Cerryl"), (b"", ""), (b"Anya", "Anya")]
+        );
+        assert_eq!(
+            names.iter_with_raw::<&str>().next_back(),
+            Some((&b"Anya"[..], "Anya"))
+        );
+        assert_eq!(names.iter_with_raw::<&str>().len(), 3);
+    }
+
+    #[test]
+    fn lengths() {
+        let names: FlatVec<String, usize, u8, 3> = ["Cerryl", "", "Anya"].iter().copied().collect();
+        assert_eq!(names.lengths().collect::<Vec<_>>(), [6, 0, 4]);
+        assert_eq!(names.lengths().rev().collect::<Vec<_>>(), [4, 0, 6]);
+        assert_eq!(FlatVec::<String, usize, u8, 3>::new().lengths().len(), 0);
+    }
+
+    #[test]
+    fn iter_range() {
+        let names: FlatVec<String, usize, u8, 3> =
+            ["Cerryl", "", "Anya", "Jeslek"].iter().copied().collect();
+        assert_eq!(names.iter_range(1..3).collect::<Vec<&str>>(), ["", "Anya"]);
+        assert_eq!(
+            names.iter_range(2..).rev().collect::<Vec<&str>>(),
+            ["Jeslek", "Anya"]
+        );
+        assert_eq!(names.iter_range(..).collect::<Vec<&str>>().len(), 4);
+        assert_eq!(names.iter_range::<&str, _>(4..).len(), 0);
+    }
+
+    #[test]
+    #[should_panic]
+    fn iter_range_out_of_bounds() {
+        let names: FlatVec<String, usize, u8, 3> = ["Cerryl"].iter().copied().collect();
+        let _ = names.iter_range::<&str, _>(..2);
+    }
+
+    #[test]
+    fn from_backing() {
+        let names: FlatVec<String, u16, u8, 3> =
+            FlatVec::from_backing(b"CerrylAnya".to_vec(), vec![6, 6, 10]).unwrap();
+        assert_eq!(names.iter().collect::<Vec<&str>>(), ["Cerryl", "", "Anya"]);
+        assert_eq!(names.data_capacity(), 10);
+
+        let err = FlatVec::<String, u16, u8, 3>::from_backing(&b"Cerryl"[..], vec![3]);
+        assert_eq!(
+            err,
+            Err(LayoutError::LastEndMismatch {
+                last_end: 3,
+                data_len: 6
+            })
+        );
+    }
+
+    #[test]
+    fn memory_usage() {
+        let mut names: FlatVec<String, u32, u8, 2> = FlatVec::new();
+        assert_eq!(names.memory_usage(), 0);
+        assert_eq!(names.heap_allocations(), 0);
+        names.push("Cerryl");
+        names.push("Jeslek");
+        assert_eq!(names.memory_usage(), names.data_capacity());
+        assert_eq!(names.heap_allocations(), 1);
+        names.push("Anya");
+        assert_eq!(
+            names.memory_usage(),
+            names.data_capacity() + 4 * names.ends_capacity()
+        );
+        assert_eq!(names.heap_allocations(), 2);
+    }
+
+    #[test]
+    fn shrink_ends_to_fit() {
+        let mut names: FlatVec<String, usize, u8, 2> =
+            ["Cerryl", "Jeslek", "Anya"].iter().copied().collect();
+        let data_capacity = names.data_capacity();
+        names.remove(0);
+        names.shrink_ends_to_fit();
+        assert!(names.is_ends_inline());
+        assert_eq!(names.data_capacity(), data_capacity);
+        assert_eq!(names.iter().collect::<Vec<&str>>(), ["Jeslek", "Anya"]);
+    }
+
+    #[test]
+    fn reset() {
+        let mut names: FlatVec<String, usize, u8, 2> =
+            ["Cerryl", "Jeslek", "Anya"].iter().copied().collect();
+        names.clear();
+        assert!(names.data_capacity() > 0);
+        assert!(!names.is_ends_inline());
+
+        names.extend(["Cerryl", "Jeslek", "Anya"].iter().copied());
+        names.reset();
+        assert!(names.is_empty());
+        assert_eq!(names.data_capacity(), 0);
+        assert!(names.is_ends_inline());
+        assert_eq!(names.heap_allocations(), 0);
+        names.push("Kiella");
+        assert_eq!(names.get(0), Some("Kiella"));
+    }
+
+    #[test]
+    fn max_data_len() {
+        let mut names: FlatVec<String, u8, u8, 3> = FlatVec::new();
+        assert_eq!(names.max_data_len(), 255);
+        names.push("Cerryl");
+        assert_eq!(names.remaining_capacity(), 249);
+        assert_eq!(
+            FlatVec::<String, u16, u8, 3>::new().max_data_len(),
+            u16::MAX as usize
+        );
+        assert_eq!(FlatVec::<String, i8, u8, 3>::new().max_data_len(), 127);
+        assert_eq!(
+            FlatVec::<String, u32, u8, 3>::new().max_data_len(),
+            u32::MAX as usize
+        );
+        assert_eq!(
+            FlatVec::<String, i64, u8, 3>::new().max_data_len(),
+            i64::MAX as usize
+        );
+        assert_eq!(
+            FlatVec::<String, usize, u8, 3>::new().max_data_len(),
+            usize::MAX
+        );
+    }
+
+    #[test]
+    fn element_chunks() {
+        let names: FlatVec<String, usize, u8, 3> = ["Cerryl", "", "Anya", "Jeslek", "Kiella"]
+            .iter()
+            .copied()
+            .collect();
+        let chunks = names.element_chunks(2);
+        assert_eq!(chunks.len(), 3);
+        let chunks = chunks.collect::<Vec<_>>();
+        assert_eq!(chunks[0].as_slice(), b"Cerryl");
+        assert_eq!(chunks[0].data_range(), 0..6);
+        assert_eq!(
+            chunks[0].raw_iter().collect::<Vec<_>>(),
+            [&b"Cerryl"[..], b""]
+        );
+        assert_eq!(chunks[1].as_slice(), b"AnyaJeslek");
+        assert_eq!(
+            chunks[1].offsets_iter().collect::<Vec<_>>(),
+            [6..10, 10..16]
+        );
+        assert_eq!(chunks[2].len(), 1);
+        assert_eq!(chunks[2].as_slice(), b"Kiella");
+
+        let empty: FlatVec<String, usize, u8, 3> = FlatVec::new();
+        assert_eq!(empty.element_chunks(2).count(), 0);
+    }
+
+    #[test]
+    fn from_data() {
+        let bytes = b"CerrylAnya".to_vec();
+        let names: FlatVec<String, u16, u8, 3, &[u8]> =
+            FlatVec::from_data(&bytes[..], vec![6, 6, 10]).unwrap();
+        assert_eq!(names.len(), 3);
+        assert_eq!(names.iter().collect::<Vec<&str>>(), ["Cerryl", "", "Anya"]);
+        assert_eq!(&names[2], b"Anya");
+        assert_eq!(names.position(|name| name == b"Anya"), Some(2));
+        assert_eq!(
+            names,
+            FlatVec::from_data(&bytes[..], vec![6, 6, 10]).unwrap()
+        );
+
+        let err = FlatVec::<String, u16, u8, 3, Vec<u8>>::from_data(bytes, vec![3]);
+        assert!(err.is_err());
+    }
+
+    #[test]
+    fn into_shared() {
+        let mut names: FlatVec<String, usize, u8, 3> = ["Cerryl", "Anya"].iter().copied().collect();
+        names.reserve_data(100);
+        let copy = names.clone();
+        assert!(!copy.ptr_eq_backing(&names));
+
+        let shared = names.into_shared();
+        let clone = shared.clone();
+        assert!(clone.ptr_eq_backing(&shared));
+        assert!(!clone.ptr_eq_backing(&copy));
+        assert_eq!(clone.iter().collect::<Vec<&str>>(), ["Cerryl", "Anya"]);
+        assert_eq!(clone.data_as_slice(), copy.data_as_slice());
+    }
+
+    #[test]
+    fn ends() {
+        let names: FlatVec<String, u8, u8, 3> = ["Cerryl", "", "Anya"].iter().copied().collect();
+        assert_eq!(names.ends(), [6, 6, 10]);
+        assert!(FlatVec::<String, u8, u8, 3>::new().ends().is_empty());
+    }
+
+    #[test]
+    fn growth_factor() {
+        let mut names: FlatVec<String, usize, u8, 3> = FlatVec::new();
+        assert_eq!(names.growth_factor(), 2.0);
+        names.push("Cerryl");
+        assert_eq!(names.data_capacity(), 6);
+        names.push("Anya");
+        assert_eq!(names.data_capacity(), 12);
+
+        let mut names: FlatVec<String, usize, u8, 3> = FlatVec::new();
+        names.set_growth_factor(1.5);
+        names.push("Cerryl");
+        names.push("Anya");
+        assert_eq!(names.data_capacity(), 10);
+        names.push("Jeslek");
+        assert_eq!(names.data_capacity(), 16);
+        assert_eq!(names.split_off(1).growth_factor(), 1.5);
+    }
+
+    #[test]
+    #[should_panic]
+    fn growth_factor_too_small() {
+        FlatVec::<String, usize, u8, 3>::new().set_growth_factor(0.5);
+    }
+
+    #[test]
+    #[should_panic]
+    fn growth_factor_infinite() {
+        FlatVec::<String, usize, u8, 3>::new().set_growth_factor(f32::INFINITY);
+    }
+
+    #[test]
+    #[should_panic]
+    fn builder_growth_factor_infinite() {
+        let _ = FlatVec::<String, usize, u8, 3>::builder().growth_factor(f32::INFINITY);
+    }
+
+    #[test]
+    #[should_panic]
+    fn builder_growth_factor_nan() {
+        let _ = FlatVec::<String, usize, u8, 3>::builder().growth_factor(f32::NAN);
+    }
+
+    #[test]
+    fn try_raw_get_and_try_iter() {
+        let names: FlatVec<String, usize, u8, 3> = ["Cerryl", "", "Anya"].iter().copied().collect();
+        assert_eq!(names.try_raw_get(0), Ok(Some(&b"Cerryl"[..])));
+        assert_eq!(names.try_raw_get(3), Ok(None));
+        assert_eq!(
+            names.try_iter().collect::<Result<Vec<&str>, _>>(),
+            Ok(vec!["Cerryl", "", "Anya"])
+        );
+
+        // Bypass validation to build the kind of layout the checked methods guard against
+        let corrupt: FlatVec<String, i8, u8, 3> = FlatVec {
+            data: Box::from(&b"Cerryl"[..]),
+            data_len: 6,
+            ends: [2, -1, 9].iter().copied().collect(),
+            growth_factor: DEFAULT_GROWTH_FACTOR,
+            marker: PhantomData,
+        };
+        assert_eq!(corrupt.try_raw_get(0), Ok(Some(&b"Ce"[..])));
+        assert_eq!(
+            corrupt.try_raw_get(1),
+            Err(LayoutError::InvalidEnd { index: 1 })
+        );
+        assert_eq!(
+            corrupt.try_raw_get(2),
+            Err(LayoutError::InvalidEnd { index: 1 })
+        );
+        assert_eq!(
+            corrupt.try_iter::<&str>().collect::<Vec<_>>(),
+            [Ok("Ce"), Err(LayoutError::InvalidEnd { index: 1 })]
+        );
+
+        let corrupt: FlatVec<String, usize, u8, 3> = FlatVec {
+            data: Box::from(&b"Cerryl"[..]),
+            data_len: 6,
+            ends: [4, 2, 9].iter().copied().collect(),
+            growth_factor: DEFAULT_GROWTH_FACTOR,
+            marker: PhantomData,
+        };
+        assert_eq!(
+            corrupt.try_raw_get(1),
+            Err(LayoutError::UnsortedEnds { index: 1 })
+        );
+        assert_eq!(
+            corrupt.try_raw_get(2),
+            Err(LayoutError::EndOutOfBounds {
+                index: 2,
+                end: 9,
+                capacity: 6
+            })
+        );
+    }
+
+    #[test]
+    fn growth_factor_headroom() {
+        let mut names: FlatVec<String, usize, u8, 3> =
+            FlatVec::builder().growth_factor(1.0).build();
+        names.push("Cerryl");
+        assert_eq!(names.data_capacity(), 6);
+        names.push("Anya");
+        assert_eq!(names.data_capacity(), 10);
+        names.reserve_data(1);
+        assert_eq!(names.data_capacity(), 11);
+    }
+
+    #[test]
+    fn from_strings() {
+        let names: FlatVec<String, usize, u8, 3> =
+            vec!["Cerryl".to_string(), "Anya".to_string()].into();
+        assert_eq!(names.iter().collect::<Vec<&str>>(), ["Cerryl", "Anya"]);
+        assert_eq!(names.data_capacity(), 10);
+
+        let names: FlatVec<String, usize, u8, 3> = FlatVec::from(&["Cerryl", "", "Anya"][..]);
+        assert_eq!(names.iter().collect::<Vec<&str>>(), ["Cerryl", "", "Anya"]);
+        assert_eq!(names.data_capacity(), 10);
+    }
+
+    #[test]
+    fn ends_capacity() {
+        let mut names: FlatVec<String, usize, u8, 2> = FlatVec::new();
+        assert_eq!(names.ends_capacity(), 2);
+        names.push("Cerryl");
+        names.push("Jeslek");
+        assert!(names.is_ends_inline());
+        names.push("Anya");
+        assert!(!names.is_ends_inline());
+        assert!(names.ends_capacity() >= 3);
+    }
+
+    #[test]
+    fn iter() {
+        let mut names: FlatVec<String, usize, u8, 3> = FlatVec::new();
+        names.push("Cerryl");
+        names.push("Jeslek");
+        let as_vec = names.iter().collect::<Vec<&str>>();
+        assert_eq!(as_vec, vec!["Cerryl".to_string(), "Jeslek".to_string()]);
+    }
+
+    #[test]
+    fn raw() {
+        let mut names: FlatVec<String, usize, u8, 3> = FlatVec::new();
+        names.push("Cerryl");
+        names.push("Jeslek");
+        assert_eq!(names.raw_get(1), Some(&b"Jeslek"[..]));
+        assert_eq!(names.raw_get(2), None);
+        assert_eq!(
+            names.raw_iter().collect::<Vec<&[u8]>>(),
+            [&b"Cerryl"[..], &b"Jeslek"[..]]
+        );
+    }
+
+    #[test]
+    fn iter_rev() {
+        let names: FlatVec<String, usize, u8, 3> =
+            ["Cerryl", "Jeslek", "Anya"].iter().copied().collect();
+        assert_eq!(
+            names.iter().rev().collect::<Vec<&str>>(),
+            ["Anya", "Jeslek", "Cerryl"]
+        );
+
+        let mut iter = names.iter::<&str>();
+        assert_eq!(iter.len(), 3);
+        assert_eq!(iter.next_back(), Some("Anya"));
+        assert_eq!(iter.next(), Some("Cerryl"));
+        assert_eq!(iter.len(), 1);
+        assert_eq!(iter.next_back(), Some("Jeslek"));
+        assert_eq!(iter.next(), None);
+        assert_eq!(iter.next_back(), None);
+    }
+
+    struct Lengths<'a> {
+        iter: Iter<'a, String, &'a str, usize, u8>,
+    }
+
+    #[test]
+    fn iter_type() {
+        let names: FlatVec<String, usize, u8, 3> =
+            ["Cerryl", "Jeslek", "Anya"].iter().copied().collect();
+        let lengths = Lengths { iter: names.iter() };
+        assert_eq!(lengths.iter.len(), 3);
+        assert_eq!(lengths.iter.map(str::len).collect::<Vec<_>>(), [6, 6, 4]);
+    }
+
+    #[test]
+    fn into_iter() {
+        let mut names: FlatVec<String, usize, u8, 3> = FlatVec::new();
+        names.push("Cerryl");
+        names.push("Jeslek");
+        let mut iter = names.into_iter();
+        assert_eq!(iter.len(), 2);
+        assert_eq!(iter.next(), Some(Owned("Cerryl".to_string())));
+        assert_eq!(iter.len(), 1);
+        assert_eq!(iter.next(), Some(Owned("Jeslek".to_string())));
+        assert_eq!(iter.next(), None);
+    }
+
+    #[test]
+    fn from_iter() {
+        let names: FlatVec<String, usize, u8, 3> =
+            ["Cerryl", "Jeslek", "Anya"].iter().copied().collect();
+        assert_eq!(names.len(), 3);
+        assert_eq!(
+            names.iter().collect::<Vec<&str>>(),
+            ["Cerryl", "Jeslek", "Anya"]
+        );
+    }
+
+    #[test]
+    fn extend() {
+        let mut names: FlatVec<String, usize, u8, 3> = FlatVec::new();
+        names.extend(["Cerryl", "Jeslek"].iter().copied());
+        assert_eq!(names.iter().collect::<Vec<&str>>(), ["Cerryl", "Jeslek"]);
+
+        names.extend(["Anya", "Sterol"].iter().copied());
+        assert_eq!(names.len(), 4);
+        assert_eq!(names.data_len(), 22);
+        assert_eq!(
+            names.iter().collect::<Vec<&str>>(),
+            ["Cerryl", "Jeslek", "Anya", "Sterol"]
+        );
+    }
+
+    #[test]
+    fn with_capacity() {
+        let mut names: FlatVec<String, usize, u8, 3> = FlatVec::with_capacity(2, 12);
+        assert!(names.is_empty());
+        assert_eq!(names.data_capacity(), 12);
+        names.push("Cerryl");
+        names.push("Jeslek");
+        assert_eq!(names.data_capacity(), 12);
+        assert_eq!(names.iter().collect::<Vec<&str>>(), ["Cerryl", "Jeslek"]);
+    }
+
+    #[test]
+    fn reserve() {
+        let mut names: FlatVec<String, usize, u8, 3> = FlatVec::new();
+        names.reserve_elements(10);
+        names.reserve_data(20);
+        assert!(names.data_capacity() >= 20);
+        names.push("Cerryl");
+        names.reserve_data(20);
+        assert!(names.data_capacity() >= 26);
+        assert_eq!(names.get(0), Some("Cerryl"));
+    }
+
+    #[test]
+    fn shrink_to_fit() {
+        let mut names: FlatVec<String, usize, u8, 3> = FlatVec::with_capacity(10, 100);
+        names.push("Cerryl");
+        names.push("Jeslek");
+        names.shrink_to_fit();
+        assert_eq!(names.data_capacity(), 12);
+        assert_eq!(names.iter().collect::<Vec<&str>>(), ["Cerryl", "Jeslek"]);
+
+        names.clear();
+        names.shrink_to_fit();
+        assert_eq!(names.data_capacity(), 0);
+        names.push("Anya");
+        assert_eq!(names.get(0), Some("Anya"));
+    }
+
+    #[test]
+    fn eq() {
+        let mut a: FlatVec<String, usize, u8, 3> = FlatVec::new();
+        let mut b: FlatVec<String, usize, u8, 3> = FlatVec::with_capacity(10, 100);
+        assert_eq!(a, b);
+
+        a.push("Cerryl");
+        assert_ne!(a, b);
+        b.push("Cerryl");
+        assert_eq!(a, b);
+
+        a.push("Jeslek");
+        b.push("Jes");
+        b.push("lek");
+        assert_ne!(a, b);
+
+        b.pop::<Owned>();
+        b.pop::<Owned>();
+        b.push("Jeslek");
+        assert_eq!(a, b);
+        assert_ne!(a.data_capacity(), b.data_capacity());
+    }
+
+    #[test]
+    fn hash() {
+        use std::collections::HashSet;
+
+        let a: FlatVec<String, usize, u8, 3> = ["ab", "c"].iter().copied().collect();
+        let b: FlatVec<String, usize, u8, 3> = ["a", "bc"].iter().copied().collect();
+        let mut c: FlatVec<String, usize, u8, 3> = FlatVec::with_capacity(10, 100);
+        c.extend(["ab", "c"].iter().copied());
+
+        let mut set = HashSet::new();
+        assert!(set.insert(a));
+        assert!(set.insert(b));
+        assert!(!set.insert(c));
+        assert_eq!(set.len(), 2);
+    }
+
+    #[test]
+    fn truncate() {
+        let mut places: FlatVec<String, usize, u8, 3> =
+            ["Cyador", "Recluce", "Hamor"].iter().copied().collect();
+        places.truncate(5);
+        assert_eq!(places.len(), 3);
+
+        places.truncate(2);
+        assert_eq!(places.iter().collect::<Vec<&str>>(), ["Cyador", "Recluce"]);
+        assert_eq!(places.data_len(), 13);
+        places.push("Hamor");
+        assert_eq!(places.get(2), Some("Hamor"));
+
+        places.truncate(0);
+        assert!(places.is_empty());
+        assert_eq!(places.data_len(), 0);
     }
-}
 
-impl<Iter, BackingTy> IntoFlat<BackingTy, Vec<BackingTy>> for Iter
-where
-    Iter: IntoIterator<Item = BackingTy>,
-    BackingTy: Default,
-{
-    #[inline]
-    fn into_flat(self, mut store: Storage<BackingTy>) {
-        store.extend(self);
+    #[test]
+    fn binary_search() {
+        let places: FlatVec<String, usize, u8, 3> = ["Cyador", "Hamor", "Recluce", "Sarronnyn"]
+            .iter()
+            .copied()
+            .collect();
+        for (i, place) in ["Cyador", "Hamor", "Recluce", "Sarronnyn"]
+            .iter()
+            .enumerate()
+        {
+            assert_eq!(
+                places.binary_search_by(|data| data.cmp(place.as_bytes())),
+                Ok(i)
+            );
+        }
+        assert_eq!(
+            places.binary_search_by(|data| data.cmp(b"Fairhaven")),
+            Err(1)
+        );
+        assert_eq!(
+            places.binary_search_by(|data| data.cmp(b"Westwind")),
+            Err(4)
+        );
+        assert_eq!(places.binary_search_by(|data| data.cmp(b"Axalt")), Err(0));
+
+        assert_eq!(
+            places.binary_search_by_key(&'R', |place: &str| place.chars().next().unwrap()),
+            Ok(2)
+        );
+        let empty: FlatVec<String, usize, u8, 3> = FlatVec::new();
+        assert_eq!(empty.binary_search_by(|data| data.cmp(b"Axalt")), Err(0));
     }
-}
 
-impl<'a, BackingTy> FromFlat<'a, BackingTy, Vec<BackingTy>> for &'a [BackingTy] {
-    #[inline]
-    fn from_flat(data: &'a [BackingTy]) -> &'a [BackingTy] {
-        data
+    #[test]
+    fn sort_unstable_by() {
+        let mut places: FlatVec<String, usize, u8, 3> = ["Sarronnyn", "Cyador", "Recluce", "Hamor"]
+            .iter()
+            .copied()
+            .collect();
+        places.sort_unstable_by(|a, b| a.cmp(b));
+        assert_eq!(
+            places.iter().collect::<Vec<&str>>(),
+            ["Cyador", "Hamor", "Recluce", "Sarronnyn"]
+        );
+        assert_eq!(places.data_len(), 27);
+
+        places.sort_unstable_by(|a, b| b.len().cmp(&a.len()));
+        assert_eq!(
+            places.iter().collect::<Vec<&str>>(),
+            ["Sarronnyn", "Recluce", "Cyador", "Hamor"]
+        );
     }
-}
 
-#[cfg(test)]
-mod tests {
-    use super::*;
+    #[test]
+    fn dedup() {
+        let mut places: FlatVec<String, usize, u8, 3> =
+            ["Cyador", "Cyador", "Hamor", "Cyador", "Recluce", "Recluce"]
+                .iter()
+                .copied()
+                .collect();
+        places.dedup();
+        assert_eq!(
+            places.iter().collect::<Vec<&str>>(),
+            ["Cyador", "Hamor", "Cyador", "Recluce"]
+        );
+        assert_eq!(places.data_len(), 24);
+
+        let mut same: FlatVec<String, usize, u8, 3> = ["Hamor"; 5].iter().copied().collect();
+        same.dedup();
+        assert_eq!(same.iter().collect::<Vec<&str>>(), ["Hamor"]);
+        assert_eq!(same.data_len(), 5);
+
+        let mut distinct: FlatVec<String, usize, u8, 3> =
+            ["Cyador", "Hamor", "Recluce"].iter().copied().collect();
+        distinct.dedup();
+        assert_eq!(
+            distinct.iter().collect::<Vec<&str>>(),
+            ["Cyador", "Hamor", "Recluce"]
+        );
+    }
 
     #[test]
-    fn push_get() {
-        let mut names: FlatVec<String, usize, u8, 3> = FlatVec::new();
-        assert!(names.is_empty());
-        assert_eq!(names.len(), 0);
-        assert_eq!(names.data_len(), 0);
+    fn split_off() {
+        let mut places: FlatVec<String, usize, u8, 3> = ["Cyador", "Recluce", "Hamor", "Sarronnyn"]
+            .iter()
+            .copied()
+            .collect();
+        let mut other = places.split_off(1);
+        assert_eq!(places.iter().collect::<Vec<&str>>(), ["Cyador"]);
+        assert_eq!(places.data_len(), 6);
+        assert_eq!(
+            other.iter().collect::<Vec<&str>>(),
+            ["Recluce", "Hamor", "Sarronnyn"]
+        );
+        assert_eq!(other.data_len(), 21);
+        other.push("Fairhaven");
+        assert_eq!(other.get(3), Some("Fairhaven"));
 
-        names.push("Cerryl");
-        assert_eq!(names.len(), 1);
-        assert!(!names.is_empty());
-        assert_eq!(names.data_len(), 6);
+        let empty = places.split_off(1);
+        assert!(empty.is_empty());
+        assert_eq!(empty.data_len(), 0);
+        let all = places.split_off(0);
+        assert!(places.is_empty());
+        assert_eq!(all.iter().collect::<Vec<&str>>(), ["Cyador"]);
+    }
 
-        names.push("Jeslek");
-        assert_eq!(names.len(), 2);
-        assert_eq!(names.data_len(), 12);
+    #[test]
+    fn append() {
+        let mut places: FlatVec<String, usize, u8, 3> =
+            ["Cyador", "Recluce"].iter().copied().collect();
+        let mut other: FlatVec<String, usize, u8, 3> =
+            ["Hamor", "Sarronnyn"].iter().copied().collect();
+        places.append(&mut other);
+        assert_eq!(
+            places.iter().collect::<Vec<&str>>(),
+            ["Cyador", "Recluce", "Hamor", "Sarronnyn"]
+        );
+        assert_eq!(places.data_len(), 27);
+        assert!(other.is_empty());
+        assert_eq!(other.data_len(), 0);
 
-        assert_eq!(names.get(0), Some("Cerryl"));
-        assert_eq!(names.get(1), Some("Jeslek"));
-        assert_eq!(names.get::<&str>(2), None);
+        places.append(&mut other);
+        assert_eq!(places.len(), 4);
+    }
 
-        assert_eq!(names.get(0), Some("Cerryl"));
-        assert_eq!(names.get(1), Some("Jeslek"));
-        assert_eq!(names.get::<&str>(2), None);
+    #[test]
+    #[should_panic]
+    fn append_overflow() {
+        let long = "a".repeat(200);
+        let mut a: FlatVec<String, u8, u8, 3> = [long.as_str()].iter().copied().collect();
+        let mut b: FlatVec<String, u8, u8, 3> = [long.as_str()].iter().copied().collect();
+        a.append(&mut b);
+    }
 
-        names.clear();
-        assert_eq!(names.len(), 0);
-        assert!(names.is_empty());
-        assert_eq!(names.data_len(), 0);
+    #[test]
+    fn merge() {
+        let vecs: Vec<FlatVec<String, usize, u8, 3>> = vec![
+            ["Cyador", "Recluce"].iter().copied().collect(),
+            FlatVec::new(),
+            ["Hamor", "Sarronnyn"].iter().copied().collect(),
+        ];
+        let merged = FlatVec::merge(vecs);
+        assert_eq!(
+            merged.iter().collect::<Vec<&str>>(),
+            ["Cyador", "Recluce", "Hamor", "Sarronnyn"]
+        );
+        assert_eq!(merged.data_capacity(), merged.data_len());
+        assert!(FlatVec::<String, usize, u8, 3>::merge(Vec::new()).is_empty());
     }
 
     #[test]
-    fn iter() {
-        let mut names: FlatVec<String, usize, u8, 3> = FlatVec::new();
-        names.push("Cerryl");
-        names.push("Jeslek");
-        let as_vec = names.iter().collect::<Vec<&str>>();
-        assert_eq!(as_vec, vec!["Cerryl".to_string(), "Jeslek".to_string()]);
+    fn raw_parts() {
+        let names: FlatVec<String, usize, u8, 3> = ["Cerryl", "Jeslek"].iter().copied().collect();
+        let (data, data_len, ends) = names.clone().into_raw_parts();
+        assert_eq!(data_len, 12);
+        assert_eq!(&ends[..], [6, 12]);
+        let rebuilt = FlatVec::from_raw_parts(data.clone(), data_len, ends).unwrap();
+        assert_eq!(names, rebuilt);
+
+        let err = |data_len, ends: &[usize]| {
+            FlatVec::<String, usize, u8, 3>::from_raw_parts(
+                data.clone(),
+                data_len,
+                ends.iter().copied().collect(),
+            )
+            .unwrap_err()
+        };
+        assert_eq!(
+            err(data.len() + 1, &[data.len() + 1]),
+            LayoutError::DataLenOutOfBounds {
+                data_len: data.len() + 1,
+                capacity: data.len()
+            }
+        );
+        assert_eq!(err(12, &[6, 3, 12]), LayoutError::UnsortedEnds { index: 1 });
+        assert_eq!(
+            err(12, &[6, 10]),
+            LayoutError::LastEndMismatch {
+                last_end: 10,
+                data_len: 12
+            }
+        );
+
+        let negative = FlatVec::<String, i32, u8, 3>::from_raw_parts(
+            Box::default(),
+            0,
+            [-1].iter().copied().collect(),
+        );
+        assert_eq!(negative.unwrap_err(), LayoutError::InvalidEnd { index: 0 });
     }
 
     #[test]
@@ -418,6 +4212,508 @@ mod tests {
         assert_eq!(places.get(1), Some("Hamor"));
     }
 
+    #[test]
+    fn try_push() {
+        let mut names: FlatVec<String, u8, u8, 3> = FlatVec::new();
+        let long = "a".repeat(200);
+        names.try_push(long.as_str()).unwrap();
+        assert_eq!(names.try_push(long.as_str()).unwrap_err().offset(), 400);
+        assert_eq!(names.len(), 1);
+        assert_eq!(names.data_len(), 200);
+        assert_eq!(names.get(0), Some(long.as_str()));
+
+        names.try_push("Cerryl").unwrap();
+        assert_eq!(names.len(), 2);
+        assert_eq!(names.data_len(), 206);
+        assert_eq!(names.get(1), Some("Cerryl"));
+    }
+
+    #[test]
+    fn push_saturating() {
+        let mut names: FlatVec<String, u8, u8, 3> = FlatVec::new();
+        let long = "a".repeat(200);
+        assert!(names.push_saturating(long.as_str()));
+        assert!(!names.push_saturating(long.as_str()));
+        assert!(names.push_saturating("Cerryl"));
+        assert_eq!(
+            names.iter().collect::<Vec<&str>>(),
+            [long.as_str(), "Cerryl"]
+        );
+        assert_eq!(names.data_len(), 206);
+    }
+
+    #[test]
+    fn swap_remove() {
+        let mut places: FlatVec<String, usize, u8, 3> = FlatVec::new();
+        places.push("Cyador");
+        places.push("Recluce");
+        places.push("Hamor");
+        places.push("Sarronnyn");
+
+        places.swap_remove(0);
+        assert_eq!(
+            places.iter().collect::<Vec<&str>>(),
+            ["Sarronnyn", "Recluce", "Hamor"]
+        );
+        assert_eq!(places.data_len(), 21);
+
+        places.swap_remove(1);
+        assert_eq!(places.iter().collect::<Vec<&str>>(), ["Sarronnyn", "Hamor"]);
+        assert_eq!(places.data_len(), 14);
+
+        places.push("Fairhaven");
+        places.swap_remove(0);
+        assert_eq!(places.iter().collect::<Vec<&str>>(), ["Fairhaven", "Hamor"]);
+
+        places.swap_remove(1);
+        assert_eq!(places.iter().collect::<Vec<&str>>(), ["Fairhaven"]);
+        places.swap_remove(0);
+        assert!(places.is_empty());
+        assert_eq!(places.data_len(), 0);
+    }
+
+    #[test]
+    fn swap() {
+        let mut places: FlatVec<String, usize, u8, 3> = ["Cyador", "Recluce", "Hamor", "Sarronnyn"]
+            .iter()
+            .copied()
+            .collect();
+        places.swap(0, 2);
+        assert_eq!(
+            places.iter().collect::<Vec<&str>>(),
+            ["Hamor", "Recluce", "Cyador", "Sarronnyn"]
+        );
+        places.swap(3, 1);
+        assert_eq!(
+            places.iter().collect::<Vec<&str>>(),
+            ["Hamor", "Sarronnyn", "Cyador", "Recluce"]
+        );
+        places.swap(2, 3);
+        assert_eq!(
+            places.iter().collect::<Vec<&str>>(),
+            ["Hamor", "Sarronnyn", "Recluce", "Cyador"]
+        );
+        places.swap(1, 1);
+        assert_eq!(places.data_len(), 27);
+
+        let mut same: FlatVec<String, usize, u8, 3> =
+            ["Cyador", "Hamor", "Raxhel"].iter().copied().collect();
+        same.swap(0, 2);
+        assert_eq!(
+            same.iter().collect::<Vec<&str>>(),
+            ["Raxhel", "Hamor", "Cyador"]
+        );
+    }
+
+    #[test]
+    fn push_with() {
+        let mut names: FlatVec<String, usize, u8, 3> = FlatVec::new();
+        names.push_with(|mut storage| storage.allocate(6).copy_from_slice(b"Cerryl"));
+        names.push_with(|mut storage| storage.extend(b"Jeslek".iter().copied()));
+        assert_eq!(names.iter().collect::<Vec<&str>>(), ["Cerryl", "Jeslek"]);
+
+        let mut names: FlatVec<String, u8, u8, 3> = FlatVec::new();
+        let result = names.try_push_with(|mut storage| {
+            storage.allocate(256);
+        });
+        assert_eq!(result.unwrap_err().offset(), 256);
+        assert!(names.is_empty());
+        assert_eq!(names.data_len(), 0);
+    }
+
+    #[test]
+    fn insert() {
+        let mut places: FlatVec<String, usize, u8, 3> = FlatVec::new();
+        places.insert(0, "Hamor");
+        places.insert(0, "Cyador");
+        places.insert(1, "Recluce");
+        places.insert(3, "Sarronnyn");
+        assert_eq!(
+            places.iter().collect::<Vec<&str>>(),
+            ["Cyador", "Recluce", "Hamor", "Sarronnyn"]
+        );
+        assert_eq!(places.data_len(), 27);
+    }
+
+    #[derive(Debug, PartialEq)]
+    struct Owned(String);
+
+    impl FromFlat<'_, u8, String> for Owned {
+        fn from_flat(data: &[u8]) -> Self {
+            Owned(str::from_utf8(data).unwrap().to_string())
+        }
+    }
+
+    #[derive(Debug, PartialEq)]
+    struct Number(u32);
+
+    impl TryFromFlat<'_, u8, Vec<u8>> for Number {
+        type Error = usize;
+        fn try_from_flat(data: &[u8]) -> Result<Self, usize> {
+            data.try_into()
+                .map(|bytes| Number(u32::from_le_bytes(bytes)))
+                .map_err(|_| data.len())
+        }
+    }
+
+    #[test]
+    fn try_get() {
+        let mut numbers: FlatVec<Vec<u8>, usize, u8, 3> = FlatVec::new();
+        numbers.push(vec![1, 0, 0, 0]);
+        numbers.push(vec![1, 2]);
+        assert_eq!(numbers.try_get(0), Some(Ok(Number(1))));
+        assert_eq!(numbers.try_get::<Number>(1), Some(Err(2)));
+        assert_eq!(numbers.try_get::<Number>(2), None);
+    }
+
+    #[test]
+    fn arrays() {
+        let mut hashes: FlatVec<[u8; 4], usize, u8, 3> = FlatVec::new();
+        hashes.push([1, 2, 3, 4]);
+        hashes.push([5, 6, 7, 8]);
+        assert_eq!(hashes.get(1), Some([5, 6, 7, 8]));
+        assert_eq!(
+            hashes.try_get::<[u8; 4]>(0).map(|r| r.ok()),
+            Some(Some([1, 2, 3, 4]))
+        );
+
+        let short: FlatVec<[u8; 4], usize, u8, 3> =
+            FlatVec::from_backing(vec![1, 2, 3], vec![3]).unwrap();
+        assert_eq!(short.try_get::<[u8; 4]>(0).map(|r| r.is_err()), Some(true));
+    }
+
+    #[test]
+    fn length_prefixed() {
+        let long = [7; 300];
+        let mut lists: FlatVec<Vec<Vec<u8>>, usize, u8, 3> = FlatVec::new();
+        lists.push(vec![&b"Cerryl"[..], b"", b"Anya"]);
+        lists.push(vec![&long[..]]);
+        lists.push(Vec::new());
+        assert_eq!(lists.raw_get(0), Some(&b"\x06Cerryl\x00\x04Anya"[..]));
+        assert_eq!(&lists.raw_get(1).unwrap()[..2], [0xac, 0x02]);
+
+        let items: LengthPrefixed = lists.get(0).unwrap();
+        assert_eq!(items.collect::<Vec<_>>(), [&b"Cerryl"[..], b"", b"Anya"]);
+        assert_eq!(lists.get(1), Some(vec![long.to_vec()]));
+        assert_eq!(lists.get::<LengthPrefixed>(2).unwrap().count(), 0);
+    }
+
+    #[test]
+    #[should_panic]
+    fn length_prefixed_truncated() {
+        let lists: FlatVec<Vec<Vec<u8>>, usize, u8, 3> =
+            FlatVec::from_backing(vec![5, 1, 2], vec![3]).unwrap();
+        let _ = lists.get::<Vec<Vec<u8>>>(0);
+    }
+
+    #[test]
+    fn try_get_str() {
+        let names: FlatVec<String, usize, u8, 3> = ["Cerryl"].iter().copied().collect();
+        let (mut data, data_len, mut ends) = names.into_raw_parts();
+        data[data_len - 1] = 0xff;
+        ends.push(data_len);
+        let names: FlatVec<String, usize, u8, 3> =
+            FlatVec::from_raw_parts(data, data_len, ends).unwrap();
+        assert_eq!(names.try_get::<&str>(0).map(|r| r.is_err()), Some(true));
+        assert_eq!(names.try_get::<&str>(1), Some(Ok("")));
+        assert_eq!(names.try_get::<&str>(2), None);
+    }
+
+    #[test]
+    fn get_into() {
+        let names: FlatVec<String, usize, u8, 3> = ["Cerryl", "Anya"].iter().copied().collect();
+        let mut name = String::with_capacity(16);
+        assert!(names.get_into(0, &mut name));
+        assert_eq!(name, "Cerryl");
+        assert!(names.get_into(1, &mut name));
+        assert_eq!(name, "Anya");
+        assert!(!names.get_into(2, &mut name));
+        assert_eq!(name, "Anya");
+        assert!(name.capacity() >= 16);
+
+        let mut bytes: FlatVec<Vec<u8>, usize, u8, 3> = FlatVec::new();
+        bytes.push(vec![1, 2, 3]);
+        let mut out = vec![4, 5, 6, 7];
+        assert!(bytes.get_into(0, &mut out));
+        assert_eq!(out, [1, 2, 3]);
+    }
+
+    #[test]
+    fn get_owned() {
+        let mut names: FlatVec<String, usize, u8, 3> = ["Cerryl"].iter().copied().collect();
+        let Owned(name) = names.get_owned(0).unwrap();
+        names.push(&*name);
+        assert_eq!(names.get(1), Some("Cerryl"));
+        assert_eq!(names.get_owned::<Owned>(2), None);
+    }
+
+    #[test]
+    fn swap_remove_into() {
+        let mut places: FlatVec<String, usize, u8, 3> =
+            ["Cyador", "Recluce", "Hamor"].iter().copied().collect();
+        assert_eq!(
+            places.swap_remove_into(0),
+            Some(Owned("Cyador".to_string()))
+        );
+        assert_eq!(places.iter().collect::<Vec<&str>>(), ["Hamor", "Recluce"]);
+        assert_eq!(places.swap_remove_into::<Owned>(2), None);
+        assert_eq!(
+            places.swap_remove_into(1),
+            Some(Owned("Recluce".to_string()))
+        );
+        assert_eq!(places.iter().collect::<Vec<&str>>(), ["Hamor"]);
+    }
+
+    #[test]
+    fn get_cow() {
+        let names: FlatVec<String, usize, u8, 3> = ["Cerryl"].iter().copied().collect();
+        let name: Cow<str> = names.get(0).unwrap();
+        assert!(matches!(name, Cow::Borrowed("Cerryl")));
+
+        let mut bytes: FlatVec<Vec<u8>, usize, u8, 3> = FlatVec::new();
+        bytes.push(vec![1, 2, 3]);
+        let mut data: Cow<[u8]> = bytes.get(0).unwrap();
+        assert!(matches!(data, Cow::Borrowed(&[1, 2, 3])));
+        data.to_mut().push(4);
+        assert_eq!(&*data, [1, 2, 3, 4]);
+    }
+
+    #[test]
+    fn to_vec() {
+        let names: FlatVec<String, usize, u8, 3> = ["Cerryl", "", "Anya"].iter().copied().collect();
+        assert_eq!(names.to_vec::<&str>(), ["Cerryl", "", "Anya"]);
+        let owned = names.into_vec::<Owned>();
+        assert_eq!(owned.len(), 3);
+        assert_eq!(owned[2], Owned("Anya".to_string()));
+    }
+
+    #[test]
+    fn pop() {
+        let mut places: FlatVec<String, usize, u8, 3> = FlatVec::new();
+        places.push("Cyador");
+        places.push("Recluce");
+
+        assert_eq!(places.pop(), Some(Owned("Recluce".to_string())));
+        assert_eq!(places.len(), 1);
+        assert_eq!(places.data_len(), 6);
+        places.push("Hamor");
+        assert_eq!(places.get(1), Some("Hamor"));
+
+        assert_eq!(places.pop(), Some(Owned("Hamor".to_string())));
+        assert_eq!(places.pop(), Some(Owned("Cyador".to_string())));
+        assert_eq!(places.pop::<Owned>(), None);
+        assert!(places.is_empty());
+        assert_eq!(places.data_len(), 0);
+    }
+
+    #[test]
+    fn set() {
+        let mut places: FlatVec<String, usize, u8, 3> =
+            ["Cyador", "Recluce", "Hamor"].iter().copied().collect();
+        places.set(1, "Axalt");
+        assert_eq!(
+            places.iter().collect::<Vec<&str>>(),
+            ["Cyador", "Axalt", "Hamor"]
+        );
+        assert_eq!(places.data_len(), 16);
+
+        places.set(0, "Sarronnyn");
+        assert_eq!(
+            places.iter().collect::<Vec<&str>>(),
+            ["Sarronnyn", "Axalt", "Hamor"]
+        );
+        assert_eq!(places.data_len(), 19);
+
+        places.set(2, "Lydia");
+        assert_eq!(
+            places.iter().collect::<Vec<&str>>(),
+            ["Sarronnyn", "Axalt", "Lydia"]
+        );
+        assert_eq!(places.data_len(), 19);
+        places.push("Hamor");
+        assert_eq!(places.get(3), Some("Hamor"));
+    }
+
+    #[test]
+    fn concat() {
+        let names: FlatVec<String, usize, u8, 3> = ["Cerryl", "", "Anya"].iter().copied().collect();
+        assert_eq!(names.concat(), b"CerrylAnya");
+        let mut out = b"names: ".to_vec();
+        names.concat_into(&mut out);
+        assert_eq!(out, b"names: CerrylAnya");
+        assert_eq!(names.join(b", "), b"Cerryl, , Anya");
+
+        let empty: FlatVec<String, usize, u8, 3> = FlatVec::new();
+        assert_eq!(empty.join(b", "), b"");
+    }
+
+    #[test]
+    fn extend_from_slice() {
+        let mut names: FlatVec<Vec<u8>, usize, u8, 3> = FlatVec::new();
+        names.push_with(|mut storage| {
+            storage.extend_from_slice(b"Cer");
+            storage.extend_from_slice(b"");
+            storage.extend_from_slice(b"ryl");
+        });
+        names.push_with(|mut storage| storage.extend_from_slice(b"Anya"));
+        assert_eq!(
+            names.iter().collect::<Vec<&[u8]>>(),
+            [&b"Cerryl"[..], b"Anya"]
+        );
+    }
+
+    #[test]
+    fn storage_offset() {
+        let mut names: FlatVec<Vec<u8>, usize, u8, 3> = FlatVec::new();
+        names.push("Cerryl".bytes());
+        names.push_with(|mut storage| {
+            assert_eq!(storage.offset(), 0);
+            storage.allocate(1);
+            assert_eq!(storage.offset(), 1);
+            storage.extend("Anya".bytes());
+            assert_eq!(storage.offset(), 5);
+            storage.extend_from_slice(b"!");
+            assert_eq!(storage.offset(), 6);
+        });
+        assert_eq!(names.raw_get(1), Some(&b"\0Anya!"[..]));
+    }
+
+    #[test]
+    fn remove_range() {
+        let mut names: FlatVec<String, usize, u8, 3> = ["Cerryl", "", "Anya", "Jeslek", "Kiella"]
+            .iter()
+            .copied()
+            .collect();
+        names.remove_range(1..3);
+        assert_eq!(
+            names.iter().collect::<Vec<&str>>(),
+            ["Cerryl", "Jeslek", "Kiella"]
+        );
+        names.remove_range(..1);
+        assert_eq!(names.iter().collect::<Vec<&str>>(), ["Jeslek", "Kiella"]);
+        names.remove_range(1..);
+        assert_eq!(names.iter().collect::<Vec<&str>>(), ["Jeslek"]);
+        assert_eq!(names.data_len(), 6);
+        names.remove_range(1..1);
+        names.push("Anya");
+        assert_eq!(names.iter().collect::<Vec<&str>>(), ["Jeslek", "Anya"]);
+        names.remove_range(..);
+        assert!(names.is_empty());
+        assert_eq!(names.data_len(), 0);
+    }
+
+    #[test]
+    fn remove_indices() {
+        let mut places: FlatVec<String, usize, u8, 3> =
+            ["Cyador", "Recluce", "Hamor", "Sarronnyn", "Fairhaven"]
+                .iter()
+                .copied()
+                .collect();
+        places.remove_indices(&[0, 2, 4]);
+        assert_eq!(
+            places.iter().collect::<Vec<&str>>(),
+            ["Recluce", "Sarronnyn"]
+        );
+        assert_eq!(places.data_len(), 16);
+        places.remove_indices(&[]);
+        assert_eq!(places.len(), 2);
+        places.remove_indices(&[0, 1]);
+        assert!(places.is_empty());
+    }
+
+    #[test]
+    #[should_panic]
+    fn remove_indices_unsorted() {
+        let mut places: FlatVec<String, usize, u8, 3> =
+            ["Cyador", "Recluce", "Hamor"].iter().copied().collect();
+        places.remove_indices(&[1, 1]);
+    }
+
+    #[test]
+    #[should_panic]
+    fn remove_indices_out_of_bounds() {
+        let mut places: FlatVec<String, usize, u8, 3> =
+            ["Cyador", "Recluce", "Hamor"].iter().copied().collect();
+        places.remove_indices(&[0, 3]);
+    }
+
+    #[test]
+    fn retain_mut() {
+        let mut names: FlatVec<String, usize, u8, 3> =
+            ["cerryl", "", "anya", "jeslek"].iter().copied().collect();
+        names.retain_mut(|name| {
+            name.make_ascii_uppercase();
+            !name.starts_with(b"A")
+        });
+        assert_eq!(
+            names.iter().collect::<Vec<&str>>(),
+            ["CERRYL", "", "JESLEK"]
+        );
+    }
+
+    #[test]
+    fn find_or_push() {
+        let mut names: FlatVec<String, usize, u8, 3> = FlatVec::new();
+        assert_eq!(names.find_or_push(b"Cerryl"), 0);
+        assert_eq!(names.find_or_push(b"Anya"), 1);
+        assert_eq!(names.find_or_push(b"Cerryl"), 0);
+        assert_eq!(names.find_or_push(b""), 2);
+        assert_eq!(names.find_or_push(b""), 2);
+        assert_eq!(names.iter().collect::<Vec<&str>>(), ["Cerryl", "Anya", ""]);
+    }
+
+    #[test]
+    fn retain() {
+        let mut places: FlatVec<String, usize, u8, 3> =
+            ["Cyador", "Recluce", "Hamor", "Sarronnyn", "Fairhaven"]
+                .iter()
+                .copied()
+                .collect();
+        let mut index = 0;
+        places.retain(|_| {
+            index += 1;
+            index % 2 == 1
+        });
+        assert_eq!(
+            places.iter().collect::<Vec<&str>>(),
+            ["Cyador", "Hamor", "Fairhaven"]
+        );
+        assert_eq!(places.data_len(), 20);
+
+        places.retain(|_| false);
+        assert!(places.is_empty());
+        assert_eq!(places.data_len(), 0);
+    }
+
+    #[test]
+    fn drain() {
+        let mut places: FlatVec<String, usize, u8, 3> =
+            ["Cyador", "Recluce", "Hamor", "Sarronnyn", "Fairhaven"]
+                .iter()
+                .copied()
+                .collect();
+        let drained = places.drain::<Owned, _>(1..3).collect::<Vec<_>>();
+        assert_eq!(
+            drained,
+            [Owned("Recluce".to_string()), Owned("Hamor".to_string())]
+        );
+        assert_eq!(
+            places.iter().collect::<Vec<&str>>(),
+            ["Cyador", "Sarronnyn", "Fairhaven"]
+        );
+        assert_eq!(places.data_len(), 24);
+
+        let mut drain = places.drain::<Owned, _>(..2);
+        assert_eq!(drain.next_back(), Some(Owned("Sarronnyn".to_string())));
+        drop(drain);
+        assert_eq!(places.iter().collect::<Vec<&str>>(), ["Fairhaven"]);
+        assert_eq!(places.data_len(), 9);
+
+        places.drain::<Owned, _>(..);
+        assert!(places.is_empty());
+        assert_eq!(places.data_len(), 0);
+    }
+
     struct Expander(usize);
 
     impl IntoFlat<usize, Vec<usize>> for Expander {
@@ -426,6 +4722,42 @@ mod tests {
         }
     }
 
+    struct BadHint {
+        remaining: usize,
+        hint: usize,
+    }
+
+    impl Iterator for BadHint {
+        type Item = usize;
+
+        fn next(&mut self) -> Option<usize> {
+            self.remaining = self.remaining.checked_sub(1)?;
+            Some(self.remaining)
+        }
+
+        fn size_hint(&self) -> (usize, Option<usize>) {
+            (self.hint, Some(self.hint))
+        }
+    }
+
+    #[test]
+    fn storage_extend_bad_hint() {
+        let mut data: FlatVec<Vec<usize>, usize, usize, 3> = FlatVec::new();
+        data.push(BadHint {
+            remaining: 2,
+            hint: 5,
+        });
+        assert_eq!(data.data_len(), 2);
+        assert_eq!(data.get(0), Some(&[1, 0][..]));
+
+        data.push(BadHint {
+            remaining: 4,
+            hint: 1,
+        });
+        assert_eq!(data.data_len(), 6);
+        assert_eq!(data.get(1), Some(&[3, 2, 1, 0][..]));
+    }
+
     #[test]
     fn storage_extend() {
         let mut data: FlatVec<Vec<usize>, usize, usize, 3> = FlatVec::new();
@@ -0,0 +1,64 @@
+//! [proptest](https://crates.io/crates/proptest) strategies for generating `FlatVec`s.
+
+use crate::{FlatVec, IntoFlat};
+use ::proptest::{collection::SizeRange, strategy::Strategy};
+use core::{convert::TryFrom, fmt, ops::Sub};
+
+/// Returns a `Strategy` that generates a `FlatVec` by pushing each element of a `Vec` generated
+/// from `element` and `size`. Generated `FlatVec`s shrink like the generated `Vec` does, by
+/// removing and shrinking elements.
+///
+/// # Panics
+///
+/// The generated `FlatVec` panics if the flattened elements do not fit in `IndexTy`, so `size`
+/// and `element` should be chosen accordingly.
+#[inline]
+pub fn flatvec_of<S, T, IndexTy, BackingTy, const INDEX_INLINE_LEN: usize>(
+    element: S,
+    size: impl Into<SizeRange>,
+) -> impl Strategy<Value = FlatVec<T, IndexTy, BackingTy, INDEX_INLINE_LEN>>
+where
+    S: Strategy,
+    S::Value: IntoFlat<BackingTy, T>,
+    IndexTy: Default,
+    IndexTy: TryFrom<usize> + Copy + Sub,
+    usize: TryFrom<IndexTy>,
+    <IndexTy as TryFrom<usize>>::Error: fmt::Debug,
+    <usize as TryFrom<IndexTy>>::Error: fmt::Debug,
+    FlatVec<T, IndexTy, BackingTy, INDEX_INLINE_LEN>: fmt::Debug,
+{
+    ::proptest::collection::vec(element, size).prop_map(|elements| elements.into_iter().collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::flatvec_of;
+    use crate::FlatVec;
+    use ::proptest::{collection::vec, prelude::*};
+    use alloc::vec::Vec;
+
+    proptest! {
+        #[test]
+        fn push_then_get(
+            mut flat in flatvec_of::<_, Vec<u8>, usize, u8, 3>(vec(any::<u8>(), 0..8), 0..16),
+            element in vec(any::<u8>(), 0..8),
+        ) {
+            let len = flat.len();
+            flat.push(element.clone());
+            prop_assert_eq!(flat.get::<&[u8]>(len), Some(&element[..]));
+        }
+
+        #[test]
+        fn remove_skips_element(
+            elements in vec(vec(any::<u8>(), 0..8), 1..16),
+            index in any::<prop::sample::Index>(),
+        ) {
+            let mut flat: FlatVec<Vec<u8>, usize, u8, 3> = elements.iter().cloned().collect();
+            let index = index.index(elements.len());
+            flat.remove(index);
+            let mut expected = elements;
+            expected.remove(index);
+            prop_assert_eq!(flat.iter::<&[u8]>().collect::<Vec<_>>(), expected);
+        }
+    }
+}
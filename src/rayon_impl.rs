@@ -0,0 +1,59 @@
+use crate::{FlatVec, FromFlat};
+use core::{convert::TryFrom, fmt, ops::Sub};
+use rayon::iter::{IndexedParallelIterator, IntoParallelIterator, ParallelIterator};
+
+impl<'a, T: 'a, IndexTy, BackingTy, const INDEX_INLINE_LEN: usize>
+    FlatVec<T, IndexTy, BackingTy, INDEX_INLINE_LEN>
+where
+    IndexTy: Default,
+    IndexTy: TryFrom<usize> + Copy + Sub,
+    usize: TryFrom<IndexTy>,
+    <IndexTy as TryFrom<usize>>::Error: fmt::Debug,
+    <usize as TryFrom<IndexTy>>::Error: fmt::Debug,
+    IndexTy: Sync,
+    BackingTy: Sync,
+{
+    /// Returns a parallel iterator that constructs a `Dest` from each element's stored
+    /// representation.
+    #[inline]
+    pub fn par_iter<Dest>(&'a self) -> impl IndexedParallelIterator<Item = Dest> + 'a
+    where
+        Dest: FromFlat<'a, BackingTy, T> + Send + 'a,
+    {
+        self.par_raw_iter().map(Dest::from_flat)
+    }
+
+    /// Returns a parallel iterator over the stored representation of each element.
+    #[inline]
+    pub fn par_raw_iter(&self) -> impl IndexedParallelIterator<Item = &[BackingTy]> + '_ {
+        let data = &self.data[..self.data_len];
+        let offsets = self.offsets_iter();
+        (0..self.len())
+            .into_par_iter()
+            .map(move |index| &data[offsets.range_of(index)])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::FlatVec;
+    use alloc::{string::String, vec::Vec};
+    use rayon::iter::{IndexedParallelIterator, ParallelIterator};
+
+    #[test]
+    fn par_iter() {
+        let places: FlatVec<String, usize, u8, 3> = ["Cyador", "Recluce", "Hamor", "Sarronnyn"]
+            .iter()
+            .copied()
+            .collect();
+        assert_eq!(places.par_iter::<&str>().len(), 4);
+        assert_eq!(
+            places.par_iter().collect::<Vec<&str>>(),
+            ["Cyador", "Recluce", "Hamor", "Sarronnyn"]
+        );
+        assert_eq!(
+            places.par_raw_iter().map(<[u8]>::len).sum::<usize>(),
+            places.data_len()
+        );
+    }
+}
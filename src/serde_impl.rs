@@ -0,0 +1,96 @@
+use crate::{validate_layout, FlatVec, DEFAULT_GROWTH_FACTOR};
+use alloc::vec::Vec;
+use core::{convert::TryFrom, marker::PhantomData};
+use serde::{de, Deserialize, Deserializer, Serialize, Serializer};
+
+// Only the live part of the backing storage is serialized, capacity is not preserved
+#[derive(Serialize)]
+#[serde(rename = "FlatVec")]
+struct FlatVecRef<'a, IndexTy, BackingTy> {
+    data: &'a [BackingTy],
+    ends: &'a [IndexTy],
+}
+
+#[derive(Deserialize)]
+#[serde(rename = "FlatVec")]
+struct FlatVecOwned<IndexTy, BackingTy> {
+    data: Vec<BackingTy>,
+    ends: Vec<IndexTy>,
+}
+
+impl<T, IndexTy, BackingTy, const INDEX_INLINE_LEN: usize> Serialize
+    for FlatVec<T, IndexTy, BackingTy, INDEX_INLINE_LEN>
+where
+    IndexTy: Default + Serialize,
+    BackingTy: Serialize,
+{
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        FlatVecRef {
+            data: &self.data[..self.data_len],
+            ends: &self.ends,
+        }
+        .serialize(serializer)
+    }
+}
+
+impl<'de, T, IndexTy, BackingTy, const INDEX_INLINE_LEN: usize> Deserialize<'de>
+    for FlatVec<T, IndexTy, BackingTy, INDEX_INLINE_LEN>
+where
+    IndexTy: Default + Copy + Deserialize<'de>,
+    usize: TryFrom<IndexTy>,
+    BackingTy: Deserialize<'de>,
+{
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let FlatVecOwned::<IndexTy, BackingTy> { data, ends } =
+            FlatVecOwned::deserialize(deserializer)?;
+
+        let () = FlatVec::<T, IndexTy, BackingTy, INDEX_INLINE_LEN>::INLINE_LEN_IS_NONZERO;
+        validate_layout(&ends, data.len(), data.len()).map_err(de::Error::custom)?;
+        Ok(FlatVec {
+            data_len: data.len(),
+            data: data.into_boxed_slice(),
+            ends: ends.into_iter().collect(),
+            growth_factor: DEFAULT_GROWTH_FACTOR,
+            marker: PhantomData,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::FlatVec;
+    use alloc::{string::String, vec::Vec};
+
+    #[test]
+    fn round_trip() {
+        let mut names: FlatVec<String, usize, u8, 3> = FlatVec::with_capacity(4, 100);
+        names.push("Cerryl");
+        names.push("Jeslek");
+
+        let json = serde_json::to_string(&names).unwrap();
+        assert_eq!(
+            json,
+            r#"{"data":[67,101,114,114,121,108,74,101,115,108,101,107],"ends":[6,12]}"#
+        );
+        let names: FlatVec<String, usize, u8, 3> = serde_json::from_str(&json).unwrap();
+        assert_eq!(names.iter().collect::<Vec<&str>>(), ["Cerryl", "Jeslek"]);
+    }
+
+    #[test]
+    fn reject_invalid() {
+        let unsorted = r#"{"data":[1,2,3],"ends":[2,1,3]}"#;
+        assert!(serde_json::from_str::<FlatVec<Vec<u8>, usize, u8, 3>>(unsorted).is_err());
+
+        let too_long = r#"{"data":[1,2,3],"ends":[1,4]}"#;
+        assert!(serde_json::from_str::<FlatVec<Vec<u8>, usize, u8, 3>>(too_long).is_err());
+
+        let too_short = r#"{"data":[1,2,3],"ends":[1]}"#;
+        assert!(serde_json::from_str::<FlatVec<Vec<u8>, usize, u8, 3>>(too_short).is_err());
+    }
+}